@@ -0,0 +1,166 @@
+/* ########################################
+# Maps between board cells and screen     #
+# pixels.                                 #
+######################################## */
+
+use olc_pixel_game_engine as olc;
+use Rect;
+
+// Bevel edge colors, blended over whatever color a cell already has
+const BEVEL_LIGHT: olc::Pixel = olc::Pixel::rgba(255, 255, 255, 96);
+const BEVEL_DARK:  olc::Pixel = olc::Pixel::rgba(0, 0, 0, 96);
+
+// Cells left around a block the camera is fitted to
+const FIT_MARGIN: i32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub x: i32,     // Screen position of the board's top-left corner
+    pub y: i32,
+    pub zoom: i32,  // Width and height of each cell on screen, in pixels
+}
+
+impl Camera {
+    // A camera with the board's top-left corner at the top-left of the screen
+    pub fn new() -> Self {
+        return Camera { x: 0, y: 0, zoom: 1 };
+    }
+
+    // Move so a board of the given size is centered on a screen of the given size
+    pub fn center(&mut self, board_width: usize, board_height: usize, screen_width: i32,
+            screen_height: i32) {
+        self.x = (screen_width - board_width as i32 * self.zoom) / 2;
+        self.y = (screen_height - board_height as i32 * self.zoom) / 2;
+    }
+
+    // Move so the given cell is in the middle of a screen of the given size
+    pub fn center_on(&mut self, x: i32, y: i32, screen_width: i32, screen_height: i32) {
+        self.x = screen_width / 2 - x * self.zoom - self.zoom / 2;
+        self.y = screen_height / 2 - y * self.zoom - self.zoom / 2;
+    }
+
+    // Zoom and move so a block of cells fills as much of a screen of the given size as it can,
+    // leaving a margin of cells around it, without zooming in past `max_zoom`
+    pub fn fit(&mut self, block: Rect, screen_width: i32, screen_height: i32, max_zoom: i32) {
+        let width = block.width as i32 + 2 * FIT_MARGIN;
+        let height = block.height as i32 + 2 * FIT_MARGIN;
+        self.zoom = (screen_width / width).min(screen_height / height).clamp(1, max_zoom.max(1));
+        self.x = (screen_width - (2 * block.x + block.width) as i32 * self.zoom) / 2;
+        self.y = (screen_height - (2 * block.y + block.height) as i32 * self.zoom) / 2;
+    }
+
+    // Get the zoom that shows about `cells` cells on a screen of the given size, at least 1
+    pub fn zoom_for_visible(screen_width: i32, screen_height: i32, cells: u64) -> i32 {
+        let area = screen_width as f64 * screen_height as f64;
+        return ((area / cells as f64).sqrt().round() as i32).max(1);
+    }
+
+    // Get the longest scale bar that fits in `max_pixels`, as a round number of cells (1, 2 or 5
+    // times a power of ten) and its length in pixels. A single cell is used if nothing fits.
+    pub fn scale_bar(&self, max_pixels: i32) -> (i32, i32) {
+        let (mut cells, mut power) = (1, 1);
+        loop {
+            for &multiple in &[1, 2, 5] {
+                if multiple * power * self.zoom > max_pixels {
+                    return (cells, cells * self.zoom);
+                }
+                cells = multiple * power;
+            }
+            power *= 10;
+        }
+    }
+
+    // Get the screen position of a cell's top-left corner
+    pub fn screen_position(&self, x: i32, y: i32) -> (i32, i32) {
+        return (x * self.zoom + self.x, y * self.zoom + self.y);
+    }
+
+    // Get the cell at a screen position, which may be off the board
+    pub fn cell_at(&self, x: i32, y: i32) -> (i32, i32) {
+        return ((x - self.x).div_euclid(self.zoom), (y - self.y).div_euclid(self.zoom));
+    }
+
+    // Draw a single cell
+    pub fn draw_cell(&self, x: usize, y: usize, color: olc::Pixel) {
+        let (x, y) = self.screen_position(x as i32, y as i32);
+        if self.zoom == 1 {
+            olc::draw(x, y, color);
+        } else {
+            olc::fill_rect(x, y, self.zoom, self.zoom, color);
+        }
+    }
+
+    // Draw a rectangle just outside a block of cells
+    pub fn draw_outline(&self, x: usize, y: usize, width: usize, height: usize, color: olc::Pixel) {
+        let (x, y) = self.screen_position(x as i32, y as i32);
+        olc::draw_rect(x - 1, y - 1, width as i32 * self.zoom + 1, height as i32 * self.zoom + 1,
+            color);
+    }
+
+    // Light a cell's top and left edges and shade its bottom and right ones, over what's already
+    // drawn there. Cells need to be a few pixels across for this to show.
+    pub fn draw_bevel(&self, x: usize, y: usize) {
+        let (left, top) = self.screen_position(x as i32, y as i32);
+        let (right, bottom) = (left + self.zoom - 1, top + self.zoom - 1);
+        olc::set_pixel_mode(olc::PixelMode::ALPHA);
+        olc::draw_line(left, top, right - 1, top, BEVEL_LIGHT);
+        olc::draw_line(left, top + 1, left, bottom - 1, BEVEL_LIGHT);
+        olc::draw_line(left + 1, bottom, right, bottom, BEVEL_DARK);
+        olc::draw_line(right, top + 1, right, bottom - 1, BEVEL_DARK);
+        olc::set_pixel_mode(olc::PixelMode::NORMAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn center_and_map_coordinates() {
+        let mut camera = Camera::new();
+        camera.center(10, 20, 200, 100);
+        assert_eq!((camera.x, camera.y), (95, 40));
+        assert_eq!(camera.screen_position(3, 4), (98, 44));
+        assert_eq!(camera.cell_at(98, 44), (3, 4));
+
+        camera.center_on(3, 4, 200, 100);
+        assert_eq!(camera.screen_position(3, 4), (100, 50));
+
+        // Zoomed in, every pixel of a cell maps back to it
+        camera.zoom = 4;
+        camera.center(10, 20, 200, 100);
+        assert_eq!((camera.x, camera.y), (80, 10));
+        assert_eq!(camera.screen_position(3, 4), (92, 26));
+        assert_eq!((camera.cell_at(92, 26), camera.cell_at(95, 29)), ((3, 4), (3, 4)));
+        assert_eq!(camera.cell_at(79, 9), (-1, -1));
+
+        // A fitted block is centered and as large as the margin allows
+        camera.fit(Rect { x: 10, y: 10, width: 6, height: 2 }, 200, 100, 16);
+        assert_eq!(camera.zoom, 16);
+        assert_eq!(camera.screen_position(13, 11), (100, 50));
+        camera.fit(Rect { x: 10, y: 10, width: 46, height: 2 }, 200, 100, 16);
+        assert_eq!(camera.zoom, 4);
+    }
+
+    #[test]
+    fn zoom_for_visible_shows_about_that_many_cells() {
+        assert_eq!(Camera::zoom_for_visible(800, 800, 10_000), 8);
+        assert_eq!(Camera::zoom_for_visible(800, 400, 10_000), 6);
+        assert_eq!(Camera::zoom_for_visible(800, 800, 1), 800);
+        // More cells than pixels still draws every cell
+        assert_eq!(Camera::zoom_for_visible(800, 800, 1_000_000_000), 1);
+    }
+
+    #[test]
+    fn scale_bar_picks_round_lengths() {
+        let mut camera = Camera::new();
+        assert_eq!(camera.scale_bar(48), (20, 20));
+        assert_eq!(camera.scale_bar(50), (50, 50));
+        camera.zoom = 4;
+        assert_eq!(camera.scale_bar(48), (10, 40));
+        camera.zoom = 16;
+        assert_eq!(camera.scale_bar(48), (2, 32));
+        camera.zoom = 64;
+        assert_eq!(camera.scale_bar(48), (1, 64));
+    }
+}
@@ -0,0 +1,214 @@
+/* ##############################################
+# Checkpoints: the board saved as RLE along     #
+# with its generation and the position of its   #
+# random number generator, so stochastic runs   #
+# can be replayed exactly.                      #
+############################################## */
+
+use std::io::BufRead;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use stats::hamming_distance;
+use GameOfLife;
+
+// Prefix of the RLE comment that holds a checkpoint, e.g.
+// `#C rustlife-checkpoint generation=40 seed=00ff... stream=0 word=1234`
+const CHECKPOINT_COMMENT: &str = "rustlife-checkpoint";
+
+// Where a random number generator is in its stream. ChaCha can jump straight to any position, so
+// this is all it takes to carry on exactly where it left off.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RngState {
+    pub seed: [u8; 32],
+    pub stream: u64,
+    pub word_pos: u128,
+}
+
+impl RngState {
+    // Take down a generator's position
+    pub fn capture(rng: &ChaCha12Rng) -> Self {
+        return RngState {
+            seed: rng.get_seed(),
+            stream: rng.get_stream(),
+            word_pos: rng.get_word_pos(),
+        };
+    }
+
+    // Make a generator at this position
+    pub fn restore(&self) -> ChaCha12Rng {
+        let mut rng = ChaCha12Rng::from_seed(self.seed);
+        rng.set_stream(self.stream);
+        rng.set_word_pos(self.word_pos);
+        return rng;
+    }
+}
+
+// The last checkpoint, kept in memory so the board can go back to it again and again without
+// reading the file or losing it
+pub struct Base {
+    state: Vec<Vec<bool>>,
+    previous: Option<Vec<Vec<bool>>>,
+    generation: u64,
+    rng: ChaCha12Rng,
+}
+
+impl Base {
+    // Take down the parts of the board a checkpoint keeps
+    pub fn capture(game: &GameOfLife) -> Self {
+        return Base {
+            state: game.state.clone(),
+            previous: game.previous.clone(),
+            generation: game.generation,
+            rng: game.rng.clone(),
+        };
+    }
+
+    // Put back the cells that changed since the checkpoint, and its generation and generator
+    // position, leaving everything else alone. Returns how many cells changed.
+    pub fn revert(&self, game: &mut GameOfLife) -> Result<usize, String> {
+        if (game.state_width, game.state_height) != (self.state.len(), self.state[0].len()) {
+            return Err("The board has changed size since the checkpoint".to_string());
+        }
+        let changed = hamming_distance(&game.state, &self.state);
+        for x in 0..game.state_width {
+            for y in 0..game.state_height {
+                if game.state[x][y] != self.state[x][y] {
+                    game.set_cell(x, y, self.state[x][y]);
+                }
+            }
+        }
+        game.previous = self.previous.clone();
+        game.generation = self.generation;
+        game.rng = self.rng.clone();
+        game.check_invariants();
+        return Ok(changed);
+    }
+}
+
+// Encode the whole board as RLE, with a comment holding the generation and generator position
+pub fn encode(game: &GameOfLife) -> String {
+    let rng = RngState::capture(&game.rng);
+    let seed: String = rng.seed.iter().map(|byte| format!("{:02x}", byte)).collect();
+    return format!("#C {} generation={} seed={} stream={} word={}\n{}", CHECKPOINT_COMMENT,
+        game.generation, seed, rng.stream, rng.word_pos, game.to_rle());
+}
+
+// Find the generation and generator position stored in a pattern's comments, if they're there
+pub fn parse(comments: &[String]) -> Result<Option<(u64, RngState)>, String> {
+    for comment in comments {
+        let mut words = comment.trim_start_matches('#').split_whitespace();
+        // Skip the comment type, like the `C` in `#C`
+        if words.next().map(|tag| tag.eq_ignore_ascii_case("C")) != Some(true)
+                || words.next() != Some(CHECKPOINT_COMMENT) {
+            continue;
+        }
+
+        let (mut generation, mut seed, mut stream, mut word_pos) = (None, None, None, None);
+        for word in words {
+            let mut parts = word.splitn(2, '=');
+            let invalid = || format!("Invalid checkpoint value `{}`", word);
+            match (parts.next(), parts.next()) {
+                (Some("generation"), Some(value)) => {
+                    generation = Some(value.parse().map_err(|_| invalid())?);
+                },
+                (Some("seed"), Some(value)) => seed = Some(parse_seed(value).ok_or_else(invalid)?),
+                (Some("stream"), Some(value)) => {
+                    stream = Some(value.parse().map_err(|_| invalid())?);
+                },
+                (Some("word"), Some(value)) => {
+                    word_pos = Some(value.parse().map_err(|_| invalid())?);
+                },
+                _ => return Err(format!("Unknown checkpoint value `{}`", word)),
+            }
+        }
+        return match (generation, seed, stream, word_pos) {
+            (Some(generation), Some(seed), Some(stream), Some(word_pos)) => {
+                Ok(Some((generation, RngState { seed: seed, stream: stream, word_pos: word_pos })))
+            },
+            _ => Err("Checkpoint is missing its generation or generator position".to_string()),
+        };
+    }
+    return Ok(None);
+}
+
+// Parse a seed written as 64 hex digits
+fn parse_seed(text: &str) -> Option<[u8; 32]> {
+    if text.len() != 64 || !text.is_ascii() {
+        return None;
+    }
+    let mut seed = [0; 32];
+    for (i, byte) in seed.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&text[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    return Some(seed);
+}
+
+// Replace the board with a checkpoint read from `reader`. Patterns without a checkpoint comment
+// load like any other, keeping the current generator. Returns whether one was found.
+pub fn restore<R: BufRead>(game: &mut GameOfLife, reader: R) -> std::io::Result<bool> {
+    let header = game.load_rle_reader(reader)?;
+    let found = parse(&header.comments)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    return Ok(match found {
+        Some((generation, rng)) => {
+            game.generation = generation;
+            game.rng = rng.restore();
+            true
+        },
+        None => false,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use UpdateMode;
+
+    #[test]
+    fn restored_checkpoint_matches_an_uninterrupted_run() {
+        let mut game = GameOfLife::new(24, 24, 7);
+        game.update_mode = UpdateMode::Random;
+        game.randomize_state();
+        game.run(5);
+        let saved = encode(&game);
+        game.run(10);
+
+        // Start from a different seed, so only the checkpoint can make it match
+        let mut resumed = GameOfLife::new(24, 24, 99);
+        resumed.update_mode = UpdateMode::Random;
+        assert!(restore(&mut resumed, saved.as_bytes()).unwrap());
+        assert_eq!(resumed.generation, 5);
+        resumed.run(10);
+        assert_eq!(resumed.state, game.state);
+        assert_eq!(resumed.generation, game.generation);
+
+        assert!(parse(&["#C rustlife-checkpoint generation=1".to_string()]).is_err());
+        assert_eq!(parse(&["#C Just a comment".to_string()]), Ok(None));
+    }
+
+    #[test]
+    fn base_reverts_changes_and_can_be_reused() {
+        let mut game = GameOfLife::new(24, 24, 7);
+        game.update_mode = UpdateMode::Random;
+        game.randomize_state();
+        game.run(3);
+        let base = Base::capture(&game);
+        let mut expected = game.clone();
+        expected.run(5);
+
+        // Each perturbation is undone, so the same random choices play out from the base
+        for &(x, y) in &[(0, 0), (5, 9)] {
+            game.state[x][y] = !game.state[x][y];
+            game.run(2);
+            assert!(base.revert(&mut game).unwrap() > 0);
+            assert_eq!(game.generation, 3);
+            game.run(5);
+            assert_eq!(game.state, expected.state);
+        }
+        assert_eq!(base.revert(&mut game).unwrap(), hamming_distance(&expected.state,
+            &base.state));
+
+        game.crop(::Rect { x: 0, y: 0, width: 4, height: 4 });
+        assert!(base.revert(&mut game).is_err());
+    }
+}
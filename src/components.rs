@@ -0,0 +1,222 @@
+/* #############################################
+# Connected components of live cells, tracked  #
+# from frame to frame so each cluster keeps    #
+# its color as it moves.                       #
+############################################# */
+
+use std::collections::HashMap;
+use camera::Camera;
+use olc_pixel_game_engine as olc;
+use Rect;
+
+// Labels the live cells of each frame with the id of the component they belong to. Cells touching
+// in any of the 8 directions are in the same component. A component keeps the id of the one it
+// overlaps from the previous frame, so ids follow clusters as they move. Components that merge,
+// and the smaller parts of ones that split, get a new id.
+pub struct Tracker {
+    pub labels: Vec<Vec<u32>>,  // Component id of each cell, indexed as `[x][y]`, or 0 if dead
+    next_id: u32,
+}
+
+impl Tracker {
+    pub fn new() -> Self {
+        return Tracker { labels: Vec::new(), next_id: 1 };
+    }
+
+    // Label a new frame, indexed as `state[x][y]`
+    pub fn update(&mut self, state: &[Vec<bool>]) {
+        let (mut labels, count) = label(state);
+        let same_size = self.labels.len() == labels.len()
+            && self.labels.first().map(|c| c.len()) == labels.first().map(|c| c.len());
+
+        // Count how many cells of each new component were in each old one
+        let mut overlaps: Vec<HashMap<u32, usize>> = vec![HashMap::new(); count + 1];
+        if same_size {
+            for x in 0..labels.len() {
+                for y in 0..labels[x].len() {
+                    if labels[x][y] != 0 && self.labels[x][y] != 0 {
+                        *overlaps[labels[x][y] as usize].entry(self.labels[x][y]).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        // Hand out old ids to the components that overlap the most of them
+        let mut ids = vec![0; count + 1];
+        let mut claims: Vec<(usize, u32, usize)> = Vec::new();
+        for (component, overlap) in overlaps.iter().enumerate() {
+            if overlap.len() == 1 {
+                let (&old, &cells) = overlap.iter().next().unwrap();
+                claims.push((cells, old, component));
+            }
+        }
+        claims.sort_by(|a, b| b.cmp(a));
+        let mut claimed = Vec::new();
+        for (_, old, component) in claims {
+            if !claimed.contains(&old) {
+                ids[component] = old;
+                claimed.push(old);
+            }
+        }
+        for id in ids.iter_mut().skip(1) {
+            if *id == 0 {
+                *id = self.next_id;
+                self.next_id += 1;
+            }
+        }
+
+        for value in labels.iter_mut().flatten() {
+            *value = ids[*value as usize];
+        }
+        self.labels = labels;
+    }
+
+    // Draw every live cell in its component's color
+    pub fn draw(&self, camera: &Camera) {
+        for x in 0..self.labels.len() {
+            for y in 0..self.labels[x].len() {
+                if self.labels[x][y] != 0 {
+                    let (r, g, b) = color(self.labels[x][y]);
+                    camera.draw_cell(x, y, olc::Pixel::rgb(r, g, b));
+                }
+            }
+        }
+    }
+}
+
+// Label the 8-connected components of live cells from 1 upward in the order they're found.
+// Returns the labels, indexed as `[x][y]` with 0 for dead cells, and the number of components.
+pub fn label(state: &[Vec<bool>]) -> (Vec<Vec<u32>>, usize) {
+    let width = state.len();
+    let height = state.first().map_or(0, |column| column.len());
+    let mut labels = vec![vec![0u32; height]; width];
+    let mut count = 0;
+    let mut stack = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            if !state[x][y] || labels[x][y] != 0 {
+                continue;
+            }
+
+            count += 1;
+            labels[x][y] = count as u32;
+            stack.push((x, y));
+            while let Some((cx, cy)) = stack.pop() {
+                for nx in cx.saturating_sub(1)..(cx + 2).min(width) {
+                    for ny in cy.saturating_sub(1)..(cy + 2).min(height) {
+                        if state[nx][ny] && labels[nx][ny] == 0 {
+                            labels[nx][ny] = count as u32;
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    return (labels, count);
+}
+
+// Get the bounding box and number of cells of each component, from labels like `label` returns.
+// Component `n` is at index `n - 1`.
+pub fn bounds(labels: &[Vec<u32>], count: usize) -> Vec<(Rect, usize)> {
+    let mut corners = vec![(usize::MAX, usize::MAX, 0, 0); count];
+    let mut cells = vec![0; count];
+    for x in 0..labels.len() {
+        for y in 0..labels[x].len() {
+            if labels[x][y] == 0 {
+                continue;
+            }
+            let index = labels[x][y] as usize - 1;
+            let (left, top, right, bottom) = &mut corners[index];
+            *left = (*left).min(x);
+            *top = (*top).min(y);
+            *right = (*right).max(x);
+            *bottom = (*bottom).max(y);
+            cells[index] += 1;
+        }
+    }
+    return corners.into_iter().zip(cells)
+        .map(|((left, top, right, bottom), cells)| {
+            let rect = Rect { x: left, y: top, width: right - left + 1, height: bottom - top + 1 };
+            return (rect, cells);
+        })
+        .collect();
+}
+
+// Pick a bright color for a component id. Nearby ids get very different colors.
+pub fn color(id: u32) -> (u8, u8, u8) {
+    // Scramble the id so consecutive ones don't get similar hues
+    let mut hash = id.wrapping_mul(0x9e37_79b9);
+    hash ^= hash >> 16;
+    return hue_color((hash % 360) as f32);
+}
+
+// Turn a hue in degrees, from 0 up to 360, into a fully saturated color at full brightness
+pub fn hue_color(hue: f32) -> (u8, u8, u8) {
+    let sector = hue / 60.0;
+    let rising = (255.0 * (1.0 - (sector % 2.0 - 1.0).abs())) as u8;
+    return match sector as u32 {
+        0 => (255, rising, 0),
+        1 => (rising, 255, 0),
+        2 => (0, 255, rising),
+        3 => (0, rising, 255),
+        4 => (rising, 0, 255),
+        _ => (255, 0, rising),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GameOfLife;
+
+    #[test]
+    fn components_are_tracked_and_merges_get_new_ids() {
+        // A glider keeps its id as it moves
+        let mut game = GameOfLife::new(20, 20, 0);
+        game.stamp_pattern(&::patterns::glider(), 2, 2);
+        game.stamp_pattern(&::patterns::glider(), 12, 2);
+        let mut tracker = Tracker::new();
+        tracker.update(&game.state);
+        let first = tracker.labels[3][2];
+        for _ in 0..8 {
+            game.update();
+            tracker.update(&game.state);
+        }
+        let (labels, count) = label(&game.state);
+        assert_eq!(count, 2);
+        let cell = (0..20).flat_map(|x| (0..20).map(move |y| (x, y)))
+            .find(|&(x, y)| labels[x][y] == 1)
+            .unwrap();
+        assert_eq!(tracker.labels[cell.0][cell.1], first);
+
+        // Two blocks joined by a bridge become one new component
+        let mut state = vec![vec![false; 8]; 4];
+        for &(x, y) in &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 5), (1, 5), (0, 6), (1, 6)] {
+            state[x][y] = true;
+        }
+        let mut tracker = Tracker::new();
+        tracker.update(&state);
+        let ids = (tracker.labels[0][0], tracker.labels[0][5]);
+        assert_ne!(ids.0, ids.1);
+        state[0][2] = true;
+        state[0][3] = true;
+        state[0][4] = true;
+        tracker.update(&state);
+        assert_eq!(tracker.labels[0][0], tracker.labels[0][5]);
+        assert!(tracker.labels[0][0] != ids.0 && tracker.labels[0][0] != ids.1);
+    }
+
+    #[test]
+    fn bounds_cover_each_component() {
+        let mut state = vec![vec![false; 8]; 8];
+        for &(x, y) in &[(1, 1), (2, 2), (3, 1), (6, 5), (6, 6)] {
+            state[x][y] = true;
+        }
+        let (labels, count) = label(&state);
+        assert_eq!(bounds(&labels, count), vec![
+            (Rect { x: 1, y: 1, width: 3, height: 2 }, 3),
+            (Rect { x: 6, y: 5, width: 1, height: 2 }, 2),
+        ]);
+    }
+}
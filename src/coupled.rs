@@ -0,0 +1,83 @@
+/* ##############################################
+# Two boards that influence each other.         #
+# Each board counts the other's live neighbors  #
+# too, scaled by the coupling strength.         #
+############################################## */
+
+use GameOfLife;
+
+// The second layer of a coupled simulation, and how strongly the layers interact.
+//
+// A cell with `own` live neighbors on its layer and `other` live neighbors on the other layer
+// (the same 8 positions, with the cell itself excluded on both) is treated as having
+//     round(own + strength * other)
+// live neighbors, clamped to 0..=8, and the result goes through its own layer's rule. Both
+// layers update at once from the previous generation. A strength of 0 makes them independent,
+// and a negative strength makes each layer inhibit the other.
+pub struct Coupling {
+    pub partner: GameOfLife,
+    pub strength: f64,
+}
+
+impl Coupling {
+    // Advance both layers by one generation
+    pub fn update(&mut self, game: &mut GameOfLife) {
+        let state = next_state(game, &self.partner, self.strength);
+        let partner_state = next_state(&self.partner, game, self.strength);
+        game.advance_to(state);
+        self.partner.advance_to(partner_state);
+    }
+}
+
+// Combine neighbor counts from both layers as described on `Coupling`
+pub fn effective_neighbors(own: u8, other: u8, strength: f64) -> u8 {
+    return (own as f64 + strength * other as f64).round().clamp(0.0, 8.0) as u8;
+}
+
+// Get the next state of `layer` under the influence of `other`
+fn next_state(layer: &GameOfLife, other: &GameOfLife, strength: f64) -> Vec<Vec<bool>> {
+    let mut state = layer.state.clone();
+    let region = layer.region();
+    for y in region.y..region.y + region.height {
+        for x in region.x..region.x + region.width {
+            let own = layer.cell_get_neighbors(x as i32, y as i32);
+            let other = other.cell_get_neighbors(x as i32, y as i32);
+            let neighbors = effective_neighbors(own, other, strength);
+            state[x][y] = layer.cell_next_state(x, y, layer.state[x][y], neighbors);
+        }
+    }
+    return state;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effective_neighbors_formula() {
+        assert_eq!(effective_neighbors(2, 1, 1.0), 3);
+        assert_eq!(effective_neighbors(2, 3, 0.5), 4);  // 3.5 rounds up
+        assert_eq!(effective_neighbors(2, 5, -1.0), 0);
+        assert_eq!(effective_neighbors(8, 8, 1.0), 8);
+    }
+
+    #[test]
+    fn partner_cells_cause_births() {
+        // Two live cells next to (1, 1) aren't enough for a birth on their own
+        let mut game = GameOfLife::new(4, 4, 0);
+        game.state[0][0] = true;
+        game.state[1][0] = true;
+        let mut partner = GameOfLife::new(4, 4, 0);
+        partner.state[2][2] = true;
+
+        let mut uncoupled = Coupling { partner: partner.clone(), strength: 0.0 };
+        let mut uncoupled_game = game.clone();
+        uncoupled.update(&mut uncoupled_game);
+        assert!(!uncoupled_game.state[1][1]);
+
+        let mut coupled = Coupling { partner: partner, strength: 1.0 };
+        coupled.update(&mut game);
+        assert!(game.state[1][1]);
+        assert_eq!(game.generation, 1);
+    }
+}
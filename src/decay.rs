@@ -0,0 +1,95 @@
+/* ##############################################
+# Decay: live cells grow more likely to die the #
+# longer they've been alive, whatever their     #
+# neighbors, on top of the rule.                #
+############################################## */
+
+use rand::Rng;
+use rand_chacha::ChaCha12Rng;
+use Rect;
+
+#[derive(Clone)]
+pub struct Decay {
+    pub probability: f64,  // Chance of death added for each generation a cell has survived
+    ages: Vec<Vec<u32>>,   // Generations each cell has been alive in a row, 1 when just born
+}
+
+impl Decay {
+    pub fn new(probability: f64, width: usize, height: usize) -> Self {
+        return Decay { probability: probability, ages: vec![vec![0; height]; width] };
+    }
+
+    // Forget every cell's age, like when the board is reset or changes size
+    pub fn reset(&mut self, width: usize, height: usize) {
+        self.ages = vec![vec![0; height]; width];
+    }
+
+    // Get how many generations in a row a cell has been alive, 0 if it's dead
+    pub fn age(&self, x: usize, y: usize) -> u32 {
+        return self.ages[x][y];
+    }
+
+    // Age the live cells inside `region` of a generation the rule just produced, then kill each
+    // with a chance of `probability` for every generation it's survived, so newborn cells are
+    // safe and a cell that's survived `1 / probability` generations is sure to die
+    pub fn apply(&mut self, state: &mut [Vec<bool>], region: Rect, rng: &mut ChaCha12Rng) {
+        for x in region.x..region.x + region.width {
+            for y in region.y..region.y + region.height {
+                if !state[x][y] {
+                    self.ages[x][y] = 0;
+                    continue;
+                }
+                let age = self.ages[x][y] + 1;
+                let chance = self.probability * (age - 1) as f64;
+                if age > 1 && rng.gen::<f64>() < chance {
+                    state[x][y] = false;
+                    self.ages[x][y] = 0;
+                } else {
+                    self.ages[x][y] = age;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GameOfLife;
+
+    #[test]
+    fn old_cells_die_and_young_ones_are_safe() {
+        // A blinker's ends are born every generation and never decay, but its center does
+        let mut game = GameOfLife::new(5, 5, 1);
+        game.decay = Some(Decay::new(0.25, 5, 5));
+        for x in 1..4 { game.state[x][2] = true; }
+        game.update();
+        assert_eq!(game.decay.as_ref().unwrap().age(2, 2), 1);
+        let mut center_died = false;
+        for _ in 0..5 {
+            game.update();
+            let decay = game.decay.as_ref().unwrap();
+            assert!(decay.age(2, 1) <= 1 && decay.age(2, 3) <= 1);
+            center_died |= !game.state[2][2] || decay.age(2, 2) == 0;
+        }
+        assert!(center_died);
+
+        // With a probability of 1, a block is sure to die as soon as it's survived once
+        let mut game = GameOfLife::new(4, 4, 2);
+        game.decay = Some(Decay::new(1.0, 4, 4));
+        for &(x, y) in &[(1, 1), (1, 2), (2, 1), (2, 2)] { game.state[x][y] = true; }
+        game.update();
+        assert_eq!(game.live_cells().len(), 4);
+        game.update();
+        assert!(game.live_cells().is_empty());
+
+        // The same seed decays the same way
+        let mut a = GameOfLife::new(24, 24, 9);
+        a.randomize_state();
+        a.decay = Some(Decay::new(0.05, 24, 24));
+        let mut b = a.clone();
+        a.run(20);
+        b.run(20);
+        assert_eq!(a.state, b.state);
+    }
+}
@@ -0,0 +1,252 @@
+/* ##############################################
+# Compact recordings of a whole run. After the  #
+# initial state, each generation only stores    #
+# which cells were born and which died.         #
+############################################## */
+
+use std::io::{self, Read, Write};
+
+// Marks the start of a delta stream, including the format version
+const MAGIC: &[u8; 8] = b"RLDELTA1";
+
+// Stream layout, with every number but the initial state written as an unsigned LEB128 varint:
+//     MAGIC, width, height, rule length, rule (UTF-8)
+//     initial state, one bit per cell in row-major order, least significant bit first
+//     then for every generation: births, then deaths
+// Each list of cells is a count followed by the cells' row-major indices in ascending order,
+// each written as the gap from the previous one, so frames with little activity take a few bytes.
+
+// One generation's changes: the cells that were born, then the ones that died, as `(x, y)`
+pub type Frame = (Vec<(usize, usize)>, Vec<(usize, usize)>);
+
+// Writes a delta stream as the simulation runs
+pub struct Recorder<W: Write> {
+    writer: W,
+    previous: Vec<Vec<bool>>,  // State as of the last recorded frame
+}
+
+impl<W: Write> Recorder<W> {
+    // Start a stream with the initial state (indexed as `state[x][y]`) and rule
+    pub fn new(mut writer: W, state: &[Vec<bool>], rule: &str) -> io::Result<Self> {
+        let (width, height) = dimensions(state);
+        writer.write_all(MAGIC)?;
+        write_varint(&mut writer, width as u64)?;
+        write_varint(&mut writer, height as u64)?;
+        write_varint(&mut writer, rule.len() as u64)?;
+        writer.write_all(rule.as_bytes())?;
+
+        let mut bits = vec![0u8; (width * height).div_ceil(8)];
+        for y in 0..height {
+            for x in 0..width {
+                if state[x][y] {
+                    let index = y * width + x;
+                    bits[index / 8] |= 1 << (index % 8);
+                }
+            }
+        }
+        writer.write_all(&bits)?;
+        return Ok(Recorder { writer: writer, previous: state.to_vec() });
+    }
+
+    // Record the changes since the last frame as one generation. This picks up changes from any
+    // source, so edits between generations are kept too.
+    pub fn record(&mut self, state: &[Vec<bool>]) -> io::Result<()> {
+        if dimensions(state) != dimensions(&self.previous) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                "the board changed size, which a delta stream can't represent"));
+        }
+
+        let (births, deaths) = diff(&self.previous, state);
+        write_cells(&mut self.writer, &births)?;
+        write_cells(&mut self.writer, &deaths)?;
+        self.previous = state.to_vec();
+        return Ok(());
+    }
+
+    // Write out anything that's been buffered
+    pub fn flush(&mut self) -> io::Result<()> {
+        return self.writer.flush();
+    }
+
+    // Get back the writer, with everything recorded so far written to it
+    pub fn into_inner(self) -> W {
+        return self.writer;
+    }
+}
+
+// Reads a delta stream back one generation at a time
+pub struct Player<R: Read> {
+    reader: R,
+    pub width: usize,
+    pub height: usize,
+    pub rule: String,
+    pub initial: Vec<Vec<bool>>,  // State at the start of the recording, indexed as `[x][y]`
+}
+
+impl<R: Read> Player<R> {
+    // Read a stream's header and initial state
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a rustlife delta stream"));
+        }
+        let width = read_varint(&mut reader)? as usize;
+        let height = read_varint(&mut reader)? as usize;
+        let mut rule = vec![0u8; read_varint(&mut reader)? as usize];
+        reader.read_exact(&mut rule)?;
+        let rule = String::from_utf8(rule).map_err(|_| invalid_data("the rule isn't UTF-8"))?;
+
+        let mut bits = vec![0u8; (width * height).div_ceil(8)];
+        reader.read_exact(&mut bits)?;
+        let mut initial = vec![vec![false; height]; width];
+        for y in 0..height {
+            for x in 0..width {
+                let index = y * width + x;
+                initial[x][y] = bits[index / 8] & (1 << (index % 8)) != 0;
+            }
+        }
+
+        return Ok(Player {
+            reader: reader,
+            width: width,
+            height: height,
+            rule: rule,
+            initial: initial,
+        });
+    }
+
+    // Read the next generation's changes, or None at the end of the stream
+    pub fn next_frame(&mut self) -> io::Result<Option<Frame>> {
+        // A stream can only end between frames
+        let births = match read_cells(&mut self.reader, self.width, self.height) {
+            Ok(births) => births,
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let deaths = read_cells(&mut self.reader, self.width, self.height)?;
+        return Ok(Some((births, deaths)));
+    }
+}
+
+// Get the width and height of a grid indexed as `state[x][y]`
+fn dimensions(state: &[Vec<bool>]) -> (usize, usize) {
+    return (state.len(), state.first().map_or(0, |column| column.len()));
+}
+
+// Get the row-major indices of the cells that were born and died between two states
+fn diff(previous: &[Vec<bool>], state: &[Vec<bool>]) -> (Vec<usize>, Vec<usize>) {
+    let (width, height) = dimensions(state);
+    let mut births = Vec::new();
+    let mut deaths = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            match (previous[x][y], state[x][y]) {
+                (false, true) => births.push(y * width + x),
+                (true, false) => deaths.push(y * width + x),
+                _ => {},
+            }
+        }
+    }
+    return (births, deaths);
+}
+
+// Write a list of ascending indices as gaps
+fn write_cells<W: Write>(writer: &mut W, indices: &[usize]) -> io::Result<()> {
+    write_varint(writer, indices.len() as u64)?;
+    let mut last = 0;
+    for &index in indices {
+        write_varint(writer, (index - last) as u64)?;
+        last = index;
+    }
+    return Ok(());
+}
+
+// Read a list written by `write_cells` and turn it back into cells
+fn read_cells<R: Read>(reader: &mut R, width: usize, height: usize)
+        -> io::Result<Vec<(usize, usize)>> {
+    let count = read_varint(reader)?;
+    let mut cells = Vec::new();
+    let mut index = 0;
+    for _ in 0..count {
+        index += read_varint(reader)? as usize;
+        if index >= width * height {
+            return Err(invalid_data("a cell is off the board"));
+        }
+        cells.push((index % width, index / width));
+    }
+    return Ok(cells);
+}
+
+// Write a number 7 bits at a time, low bits first, with the top bit set on all but the last byte
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            return writer.write_all(&[byte]);
+        }
+        writer.write_all(&[byte | 0x80])?;
+    }
+}
+
+// Read a number written by `write_varint`
+fn read_varint<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0u8];
+        reader.read_exact(&mut byte)?;
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    return Err(invalid_data("a number is too long"));
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    return io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GameOfLife;
+
+    #[test]
+    fn playback_reconstructs_recording() {
+        let mut game = GameOfLife::new(30, 20, 3);
+        game.randomize_state();
+        let mut recorder = Recorder::new(Vec::new(), &game.state, "B3/S23").unwrap();
+        let mut states = Vec::new();
+        for _ in 0..10 {
+            game.update();
+            recorder.record(&game.state).unwrap();
+            states.push(game.state.clone());
+        }
+
+        let mut player = Player::new(&recorder.writer[..]).unwrap();
+        assert_eq!((player.width, player.height, player.rule.as_str()), (30, 20, "B3/S23"));
+        let mut state = player.initial.clone();
+        for expected in states {
+            let (births, deaths) = player.next_frame().unwrap().unwrap();
+            for (x, y) in births {
+                state[x][y] = true;
+            }
+            for (x, y) in deaths {
+                state[x][y] = false;
+            }
+            assert_eq!(state, expected);
+        }
+        assert!(player.next_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn quiet_generations_are_tiny() {
+        let state = vec![vec![false; 100]; 100];
+        let mut recorder = Recorder::new(Vec::new(), &state, "B3/S23").unwrap();
+        let header = recorder.writer.len();
+        recorder.record(&state).unwrap();
+        assert_eq!(recorder.writer.len() - header, 2);
+    }
+}
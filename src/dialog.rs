@@ -0,0 +1,196 @@
+/* ############################################
+# Save dialog, where a filename is typed in   #
+# and a format picked before saving.          #
+############################################ */
+
+use olc_pixel_game_engine as olc;
+
+// Longest filename that can be typed, not counting the extension
+const MAX_NAME_LENGTH: usize = 24;
+
+// Dialog colors
+const DIALOG_BACKGROUND: olc::Pixel = olc::VERY_DARK_GREY;
+const DIALOG_BORDER:     olc::Pixel = olc::GREY;
+const DIALOG_MESSAGE:    olc::Pixel = olc::YELLOW;
+
+// Keys that type a character, lowercase unless shift is held
+const LETTER_KEYS: [(olc::Key, char); 26] = [
+    (olc::Key::A, 'a'), (olc::Key::B, 'b'), (olc::Key::C, 'c'), (olc::Key::D, 'd'),
+    (olc::Key::E, 'e'), (olc::Key::F, 'f'), (olc::Key::G, 'g'), (olc::Key::H, 'h'),
+    (olc::Key::I, 'i'), (olc::Key::J, 'j'), (olc::Key::K, 'k'), (olc::Key::L, 'l'),
+    (olc::Key::M, 'm'), (olc::Key::N, 'n'), (olc::Key::O, 'o'), (olc::Key::P, 'p'),
+    (olc::Key::Q, 'q'), (olc::Key::R, 'r'), (olc::Key::S, 's'), (olc::Key::T, 't'),
+    (olc::Key::U, 'u'), (olc::Key::V, 'v'), (olc::Key::W, 'w'), (olc::Key::X, 'x'),
+    (olc::Key::Y, 'y'), (olc::Key::Z, 'z'),
+];
+const OTHER_KEYS: [(olc::Key, char); 22] = [
+    (olc::Key::K0, '0'), (olc::Key::K1, '1'), (olc::Key::K2, '2'), (olc::Key::K3, '3'),
+    (olc::Key::K4, '4'), (olc::Key::K5, '5'), (olc::Key::K6, '6'), (olc::Key::K7, '7'),
+    (olc::Key::K8, '8'), (olc::Key::K9, '9'),
+    (olc::Key::NP0, '0'), (olc::Key::NP1, '1'), (olc::Key::NP2, '2'), (olc::Key::NP3, '3'),
+    (olc::Key::NP4, '4'), (olc::Key::NP5, '5'), (olc::Key::NP6, '6'), (olc::Key::NP7, '7'),
+    (olc::Key::NP8, '8'), (olc::Key::NP9, '9'),
+    (olc::Key::NP_SUB, '-'), (olc::Key::SPACE, '_'),
+];
+
+// A format the board can be saved in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Rle,
+    Svg,
+}
+
+impl Format {
+    // Get the extension added to filenames saved in this format
+    pub fn extension(self) -> &'static str {
+        return match self {
+            Format::Rle => "rle",
+            Format::Svg => "svg",
+        };
+    }
+
+    // Get the format after this one
+    fn next(self) -> Self {
+        return match self {
+            Format::Rle => Format::Svg,
+            Format::Svg => Format::Rle,
+        };
+    }
+}
+
+// What the user did with the dialog this frame
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    Editing,
+    Cancel,
+    Save(String, Format),  // Path to save to, which has been checked
+}
+
+// An open save dialog
+pub struct SaveDialog {
+    pub name:    String,
+    pub format:  Format,
+    pub message: Option<String>,  // Problem with the last attempt to save, shown under the name
+    confirming:  bool,            // Whether the next save overwrites an existing file
+}
+
+impl SaveDialog {
+    // Open a dialog with a name filled in, which can be typed over
+    pub fn new(name: &str) -> Self {
+        return SaveDialog {
+            name: name.to_string(),
+            format: Format::Rle,
+            message: None,
+            confirming: false,
+        };
+    }
+
+    // Get the path the dialog would save to
+    pub fn path(&self) -> String {
+        return format!("{}.{}", self.name, self.format.extension());
+    }
+
+    // Handle this frame's key presses. Letters and digits type, SPACE types `_`, BACKSPACE
+    // deletes, TAB switches the format, ENTER saves and ESCAPE closes the dialog.
+    pub fn handle_input(&mut self) -> Action {
+        if olc::get_key(olc::Key::ESCAPE).pressed {
+            return Action::Cancel;
+        }
+        if olc::get_key(olc::Key::RETURN).pressed || olc::get_key(olc::Key::ENTER).pressed {
+            return self.submit(std::path::Path::new(&self.path()).exists());
+        }
+
+        let edited_before = (self.name.clone(), self.format);
+        if olc::get_key(olc::Key::BACK).pressed {
+            self.name.pop();
+        } else if olc::get_key(olc::Key::TAB).pressed {
+            self.format = self.format.next();
+        }
+        let shift = olc::get_key(olc::Key::SHIFT).held;
+        for &(key, c) in LETTER_KEYS.iter().chain(OTHER_KEYS.iter()) {
+            if olc::get_key(key).pressed && self.name.len() < MAX_NAME_LENGTH {
+                self.name.push(if shift { c.to_ascii_uppercase() } else { c });
+            }
+        }
+
+        // Changing anything needs a fresh confirmation
+        if (self.name.clone(), self.format) != edited_before {
+            self.confirming = false;
+            self.message = None;
+        }
+        return Action::Editing;
+    }
+
+    // Try to save, given whether the file already exists. Problems are left in the message.
+    fn submit(&mut self, exists: bool) -> Action {
+        if let Err(e) = validate_name(&self.name) {
+            self.message = Some(e);
+            return Action::Editing;
+        }
+        if exists && !self.confirming {
+            self.confirming = true;
+            self.message = Some("File exists. ENTER again to overwrite".to_string());
+            return Action::Editing;
+        }
+        self.confirming = false;
+        return Action::Save(self.path(), self.format);
+    }
+
+    // Draw the dialog across the top of the screen
+    pub fn draw(&self) -> Result<(), olc::Error> {
+        let height = if self.message.is_some() { 42 } else { 32 };
+        olc::fill_rect(0, 0, olc::screen_width(), height, DIALOG_BACKGROUND);
+        olc::draw_rect(0, 0, olc::screen_width() - 1, height - 1, DIALOG_BORDER);
+        olc::draw_string(2, 2, &format!("Save as {}:", self.format.extension().to_uppercase()),
+            olc::WHITE)?;
+        olc::draw_string(2, 12, &format!("{}_", self.path()), olc::WHITE)?;
+        olc::draw_string(2, 22, "TAB format ENTER save ESC cancel", DIALOG_BORDER)?;
+        if let Some(message) = &self.message {
+            olc::draw_string(2, 32, message, DIALOG_MESSAGE)?;
+        }
+        return Ok(());
+    }
+}
+
+// Check that a filename can be saved to. Names typed into the dialog only have letters, digits,
+// `-` and `_`, but ones filled in beforehand might not.
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Type a name first".to_string());
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(format!("Names can be at most {} characters", MAX_NAME_LENGTH));
+    }
+    if let Some(c) = name.chars().find(|&c| !(c.is_ascii_alphanumeric() || c == '-' || c == '_')) {
+        return Err(format!("Names can't contain `{}`", c));
+    }
+    if name.starts_with('-') {
+        return Err("Names can't start with `-`".to_string());
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn submitting_validates_and_confirms_overwrites() {
+        assert!(validate_name("glider-gun_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../escape").is_err());
+        assert!(validate_name("-flag").is_err());
+
+        let mut dialog = SaveDialog::new("gun");
+        dialog.format = Format::Svg;
+        assert_eq!(dialog.submit(false), Action::Save("gun.svg".to_string(), Format::Svg));
+
+        // An existing file takes a second ENTER
+        assert_eq!(dialog.submit(true), Action::Editing);
+        assert!(dialog.message.is_some());
+        assert_eq!(dialog.submit(true), Action::Save("gun.svg".to_string(), Format::Svg));
+
+        let mut dialog = SaveDialog::new("");
+        assert_eq!(dialog.submit(false), Action::Editing);
+    }
+}
@@ -0,0 +1,140 @@
+/* ##############################################
+# Hints at Garden of Eden states, which have no #
+# predecessor. This only looks at 3x3 patches,  #
+# so it's a heuristic rather than a proof.      #
+############################################## */
+
+use camera::Camera;
+use olc_pixel_game_engine as olc;
+use rule::Rule;
+
+// Tint for cells in a patch that can't have a predecessor
+const ORPHAN_COLOR:      olc::Pixel = olc::Pixel::rgb(96, 0, 0);
+const ORPHAN_LIVE_COLOR: olc::Pixel = olc::Pixel::rgb(255, 96, 96);
+
+// Which 3x3 patches can appear under a rule. A patch is indexed by its cells as bits, with the
+// cell at (x, y) in bit `y * 3 + x`.
+//
+// Every patch depends only on the 5x5 area around it in the previous generation, so a patch
+// nothing in the previous generation can produce is an orphan, and any board containing it is a
+// Garden of Eden. The reverse doesn't hold: a board can be a Garden of Eden with only reachable
+// patches, when their predecessors can't be made to agree. Under Life every 3x3 patch is
+// reachable, and the smallest known orphans are much larger.
+pub struct Hint {
+    pub rule: Rule,
+    reachable: Vec<bool>,
+}
+
+impl Hint {
+    // Find the reachable patches under a rule
+    pub fn new(rule: Rule) -> Self {
+        // Each output row of a patch comes from three 5-cell rows of the previous generation
+        let mut row_output = vec![0u8; 1 << 15];
+        for (rows, output) in row_output.iter_mut().enumerate() {
+            let (above, middle, below) = (rows >> 10, (rows >> 5) & 31, rows & 31);
+            for x in 0..3 {
+                let bit = |row: usize, dx: usize| (row >> (x + dx)) & 1;
+                let neighbors = bit(above, 0) + bit(above, 1) + bit(above, 2) + bit(middle, 0)
+                    + bit(middle, 2) + bit(below, 0) + bit(below, 1) + bit(below, 2);
+                if rule.next_state(bit(middle, 1) == 1, neighbors as u8) {
+                    *output |= 1 << x;
+                }
+            }
+        }
+
+        // Build the previous generation a row at a time, keeping only the last two rows and the
+        // output rows so far. That's a few million steps instead of trying all 2^25 areas.
+        let output = |a: usize, b: usize, c: usize| row_output[(a << 10) | (b << 5) | c] as usize;
+        let mut after_three = vec![false; 1 << 13];  // Rows 1-2, then one output row
+        for a in 0..32 {
+            for b in 0..32 {
+                for c in 0..32 {
+                    after_three[(b << 8) | (c << 3) | output(a, b, c)] = true;
+                }
+            }
+        }
+        let mut after_four = vec![false; 1 << 16];  // Rows 2-3, then two output rows
+        for (state, _) in after_three.iter().enumerate().filter(|&(_, &seen)| seen) {
+            let (b, c, outputs) = (state >> 8, (state >> 3) & 31, state & 7);
+            for d in 0..32 {
+                after_four[(c << 11) | (d << 6) | (output(b, c, d) << 3) | outputs] = true;
+            }
+        }
+        let mut reachable = vec![false; 512];
+        for (state, _) in after_four.iter().enumerate().filter(|&(_, &seen)| seen) {
+            let (c, d, outputs) = (state >> 11, (state >> 6) & 31, state & 63);
+            for e in 0..32 {
+                reachable[(output(c, d, e) << 6) | outputs] = true;
+            }
+        }
+        return Hint { rule: rule, reachable: reachable };
+    }
+
+    // Check whether a 3x3 patch can appear under the rule
+    pub fn is_reachable(&self, patch: usize) -> bool {
+        return self.reachable[patch];
+    }
+
+    // Mark every cell of each 3x3 patch on the board (indexed as `state[x][y]`) that can't have a
+    // predecessor. Patches that go past the edge of the board aren't checked.
+    pub fn orphan_cells(&self, state: &[Vec<bool>]) -> Vec<Vec<bool>> {
+        let width = state.len();
+        let height = state.first().map_or(0, |column| column.len());
+        let mut orphans = vec![vec![false; height]; width];
+        for top in 0..height.saturating_sub(2) {
+            for left in 0..width.saturating_sub(2) {
+                let mut patch = 0;
+                for y in 0..3 {
+                    for x in 0..3 {
+                        if state[left + x][top + y] {
+                            patch |= 1 << (y * 3 + x);
+                        }
+                    }
+                }
+                if !self.is_reachable(patch) {
+                    for y in 0..3 {
+                        for x in 0..3 {
+                            orphans[left + x][top + y] = true;
+                        }
+                    }
+                }
+            }
+        }
+        return orphans;
+    }
+
+    // Tint the cells of every orphan patch
+    pub fn draw(&self, state: &[Vec<bool>], camera: &Camera) {
+        let orphans = self.orphan_cells(state);
+        for x in 0..orphans.len() {
+            for y in 0..orphans[x].len() {
+                if orphans[x][y] {
+                    let color = if state[x][y] { ORPHAN_LIVE_COLOR } else { ORPHAN_COLOR };
+                    camera.draw_cell(x, y, color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reachable_patches() {
+        // Under Life everything is reachable
+        let life = Hint::new(Rule::life());
+        assert!((0..512).all(|patch| life.is_reachable(patch)));
+
+        // Under B/S everything dies, so only the empty patch is reachable
+        let empty = Hint::new("B/S".parse().unwrap());
+        assert!(empty.is_reachable(0));
+        assert!((1..512).all(|patch| !empty.is_reachable(patch)));
+
+        let mut state = vec![vec![false; 5]; 5];
+        state[4][4] = true;
+        let orphans = empty.orphan_cells(&state);
+        assert!(orphans[2][2] && orphans[4][4] && !orphans[1][1]);
+    }
+}
@@ -0,0 +1,153 @@
+/* ##############################################
+# Visual effects layered over the board.        #
+# Effects are timed in seconds rather than      #
+# generations, so they keep animating to rest   #
+# while the simulation is paused.               #
+############################################## */
+
+use camera::Camera;
+use olc_pixel_game_engine as olc;
+
+// How long effects take to finish, in seconds
+const TRAIL_TIME: f32 = 0.5;
+const FLASH_TIME: f32 = 0.25;
+
+// Brightest a trail gets, out of 255
+const TRAIL_BRIGHTNESS: f32 = 160.0;
+
+pub struct Effects {
+    pub trails: bool,              // Whether cells that die fade out instead of vanishing
+    pub flash: bool,               // Whether cells that are born flash yellow
+    pub motion_blur: Option<u32>,  // Frames a live cell's image takes to fade after it moves on
+    previous: Vec<Vec<bool>>,      // State seen on the last call to `observe`
+    trail: Vec<Vec<f32>>,          // Remaining trail of each cell, from 1 down to 0
+    glow: Vec<Vec<f32>>,           // Remaining flash of each cell, from 1 down to 0
+    blur: Vec<Vec<f32>>,           // Recent liveness of each cell, 1 while alive and fading after
+}
+
+impl Effects {
+    pub fn new() -> Self {
+        return Effects {
+            trails: false,
+            flash: false,
+            motion_blur: None,
+            previous: Vec::new(),
+            trail: Vec::new(),
+            glow: Vec::new(),
+            blur: Vec::new(),
+        };
+    }
+
+    // Start effects on cells that changed since the last call. This picks up changes from any
+    // source, whether that's a generation, an edit or a reset.
+    pub fn observe(&mut self, state: &[Vec<bool>]) {
+        let width = state.len();
+        let height = state.first().map_or(0, |column| column.len());
+        if self.previous.len() != width || self.previous.first().map_or(0, |c| c.len()) != height {
+            // Start over on a board of a new size, without effects for the initial state
+            self.previous = state.to_vec();
+            self.trail = vec![vec![0.0; height]; width];
+            self.glow = vec![vec![0.0; height]; width];
+            self.blur = state.iter()
+                .map(|column| column.iter().map(|&alive| if alive { 1.0 } else { 0.0 }).collect())
+                .collect();
+            return;
+        }
+
+        for x in 0..width {
+            for y in 0..height {
+                if self.previous[x][y] && !state[x][y] {
+                    self.trail[x][y] = 1.0;
+                    self.glow[x][y] = 0.0;
+                } else if !self.previous[x][y] && state[x][y] {
+                    self.glow[x][y] = 1.0;
+                    self.trail[x][y] = 0.0;
+                }
+                self.previous[x][y] = state[x][y];
+            }
+        }
+
+        // Unlike the other effects, motion blur is counted in frames. This is called once per
+        // frame, so fast movers leave a smear as long as the blur, whatever the update rate.
+        if let Some(frames) = self.motion_blur {
+            let fade = 1.0 / frames.max(1) as f32;
+            for x in 0..width {
+                for y in 0..height {
+                    let faded = (self.blur[x][y] - fade).max(0.0);
+                    self.blur[x][y] = if state[x][y] { 1.0 } else { faded };
+                }
+            }
+        }
+    }
+
+    // Advance every effect by `elapsed_time` seconds
+    pub fn advance(&mut self, elapsed_time: f32) {
+        let decay = |values: &mut Vec<Vec<f32>>, duration: f32| {
+            for value in values.iter_mut().flatten() {
+                *value = (*value - elapsed_time / duration).max(0.0);
+            }
+        };
+        decay(&mut self.trail, TRAIL_TIME);
+        decay(&mut self.glow, FLASH_TIME);
+    }
+
+    // Draw the enabled effects over the board
+    pub fn draw(&self, camera: &Camera) {
+        for x in 0..self.previous.len() {
+            for y in 0..self.previous[x].len() {
+                if self.trails && !self.previous[x][y] && self.trail[x][y] > 0.0 {
+                    let level = (self.trail[x][y] * TRAIL_BRIGHTNESS) as u8;
+                    camera.draw_cell(x, y, olc::Pixel::rgb(level, level, level));
+                } else if self.flash && self.previous[x][y] && self.glow[x][y] > 0.0 {
+                    // Fade from yellow back to the usual white
+                    let blue = ((1.0 - self.glow[x][y]) * 255.0) as u8;
+                    camera.draw_cell(x, y, olc::Pixel::rgb(255, 255, blue));
+                } else if self.motion_blur.is_some() && !self.previous[x][y]
+                        && self.blur[x][y] > 0.0 {
+                    // Blend toward the live color, so movers look smeared in their direction
+                    let level = (self.blur[x][y] * 255.0) as u8;
+                    camera.draw_cell(x, y, olc::Pixel::rgb(level, level, level));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effects_finish_without_new_generations() {
+        let mut effects = Effects::new();
+        let mut state = vec![vec![false; 2]; 2];
+        state[0][0] = true;
+        effects.observe(&state);
+
+        // One cell dies and another is born, then time passes with no more changes
+        state[0][0] = false;
+        state[1][1] = true;
+        effects.observe(&state);
+        assert_eq!((effects.trail[0][0], effects.glow[1][1]), (1.0, 1.0));
+
+        for _ in 0..40 {
+            effects.advance(1.0 / 60.0);
+            effects.observe(&state);
+        }
+        assert_eq!((effects.trail[0][0], effects.glow[1][1]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn motion_blur_fades_over_its_length() {
+        let mut effects = Effects::new();
+        effects.motion_blur = Some(4);
+        let mut state = vec![vec![true]];
+        effects.observe(&state);
+
+        state[0][0] = false;
+        for frame in 1..=4 {
+            effects.observe(&state);
+            assert_eq!(effects.blur[0][0], 1.0 - frame as f32 / 4.0);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+/* ##############################################
+# Rule-space explorer: a grid of small boards   #
+# started from the same soup, each running a    #
+# rule one birth or survival count away from    #
+# the current one.                              #
+############################################## */
+
+use camera::Camera;
+use olc_pixel_game_engine as olc;
+use rule::Rule;
+use {GameOfLife, Topology};
+
+// Width and height of each thumbnail's board
+const THUMBNAIL_CELLS: usize = 32;
+
+// Pixels between thumbnails
+const THUMBNAIL_GAP: i32 = 2;
+
+// Height kept free at the bottom of the screen for the rule under the pointer
+const CAPTION_HEIGHT: i32 = 10;
+
+// Explorer colors
+const CURRENT_RULE_COLOR: olc::Pixel = olc::YELLOW;
+const HOVER_COLOR:        olc::Pixel = olc::CYAN;
+const THUMBNAIL_COLOR:    olc::Pixel = olc::DARK_GREY;
+
+// Where the thumbnails go on a screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Layout {
+    columns: usize,
+    pitch:   i32,  // Distance from one thumbnail to the next, across or down
+    zoom:    i32,  // Pixels across each cell
+}
+
+pub struct Explorer {
+    pub games: Vec<GameOfLife>,  // The current rule first, then every rule one count away
+}
+
+impl Explorer {
+    // Start a board for `rule` and for each of its mutations, all from the same random soup
+    pub fn new(rule: Rule, density: f64, seed: u64) -> Self {
+        let rules = std::iter::once(rule).chain(rule.mutations());
+        let games = rules.map(|rule| {
+            let mut game = GameOfLife::new(THUMBNAIL_CELLS, THUMBNAIL_CELLS, seed);
+            game.rule = rule;
+            game.density = density;
+            // Wrapping keeps small boards from being dominated by what happens at their edges
+            game.topology = Topology::Torus;
+            game.randomize_state();
+            game
+        }).collect();
+        return Explorer { games: games };
+    }
+
+    // Advance every board by one generation
+    pub fn update(&mut self) {
+        for game in &mut self.games {
+            game.update();
+        }
+    }
+
+    // Lay the thumbnails out in a roughly square grid, as large as the screen allows
+    fn layout(&self, screen_width: i32, screen_height: i32) -> Layout {
+        let count = self.games.len();
+        let columns = (1..=count).find(|&columns| columns * columns >= count).unwrap_or(1);
+        let rows = count.div_ceil(columns) as i32;
+        let pitch = (screen_width / columns as i32).min((screen_height - CAPTION_HEIGHT) / rows);
+        let zoom = ((pitch - THUMBNAIL_GAP) / THUMBNAIL_CELLS as i32).max(1);
+        return Layout { columns: columns, pitch: pitch, zoom: zoom };
+    }
+
+    // Get the camera that draws a thumbnail in its place on the screen
+    fn camera(&self, index: usize, layout: Layout) -> Camera {
+        let column = (index % layout.columns) as i32;
+        let row = (index / layout.columns) as i32;
+        return Camera {
+            x: column * layout.pitch + THUMBNAIL_GAP / 2,
+            y: row * layout.pitch + THUMBNAIL_GAP / 2,
+            zoom: layout.zoom,
+        };
+    }
+
+    // Get the thumbnail at a screen position, if there is one
+    pub fn thumbnail_at(&self, x: i32, y: i32, screen_width: i32, screen_height: i32)
+            -> Option<usize> {
+        let layout = self.layout(screen_width, screen_height);
+        return (0..self.games.len()).find(|&index| {
+            let (cell_x, cell_y) = self.camera(index, layout).cell_at(x, y);
+            let cells = 0..THUMBNAIL_CELLS as i32;
+            cells.contains(&cell_x) && cells.contains(&cell_y)
+        });
+    }
+
+    // Draw every thumbnail, outlining the current rule and the one under the pointer, and name
+    // the rule under the pointer along the bottom of the screen
+    pub fn draw(&self, hovered: Option<usize>) -> Result<(), olc::Error> {
+        let (screen_width, screen_height) = (olc::screen_width(), olc::screen_height());
+        let layout = self.layout(screen_width, screen_height);
+        olc::clear(olc::BLACK);
+        for (index, game) in self.games.iter().enumerate() {
+            let camera = self.camera(index, layout);
+            for (x, y) in game.live_cells() {
+                camera.draw_cell(x as usize, y as usize, olc::WHITE);
+            }
+            let color = if hovered == Some(index) {
+                HOVER_COLOR
+            } else if index == 0 {
+                CURRENT_RULE_COLOR
+            } else {
+                THUMBNAIL_COLOR
+            };
+            camera.draw_outline(0, 0, THUMBNAIL_CELLS, THUMBNAIL_CELLS, color);
+        }
+
+        let caption = match hovered {
+            Some(0) => format!("{} (current)", self.games[0].rule),
+            Some(index) => format!("{} click to use", self.games[index].rule),
+            None => "Y or ESC to go back".to_string(),
+        };
+        olc::draw_string(1, screen_height - CAPTION_HEIGHT + 1, &caption, olc::WHITE)?;
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumbnails_share_a_soup_and_fit_the_screen() {
+        let explorer = Explorer::new(Rule::life(), 0.5, 7);
+        assert_eq!(explorer.games.len(), 18);
+        assert_eq!(explorer.games[0].rule, Rule::life());
+        assert!(explorer.games.iter().all(|game| game.state == explorer.games[0].state));
+
+        // 18 thumbnails make a 5 by 4 grid, zoomed in as far as they fit
+        assert_eq!(explorer.layout(200, 200), Layout { columns: 5, pitch: 40, zoom: 1 });
+        assert_eq!(explorer.layout(400, 400).zoom, 2);
+        assert_eq!(explorer.thumbnail_at(1, 1, 200, 200), Some(0));
+        assert_eq!(explorer.thumbnail_at(41, 81, 200, 200), Some(11));
+        assert_eq!(explorer.thumbnail_at(38, 1, 200, 200), None);
+        assert_eq!(explorer.thumbnail_at(199, 199, 200, 200), None);
+    }
+}
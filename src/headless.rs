@@ -0,0 +1,715 @@
+/* ###########################################
+# Modes that run the simulation without      #
+# opening a window.                          #
+########################################### */
+
+use std::time::{Duration, Instant};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use components;
+use deltas::Recorder;
+use html;
+use patterns::{Dictionary, Pattern};
+use png;
+use sparse::SparseLife;
+use stats::{hamming_distance, Detector, Event};
+use {GameOfLife, Rect, UpdateMode};
+
+// Prefix of the RLE comment that pins a verification run, e.g.
+// `#C rustlife-verify generations=100 hash=0123456789abcdef`
+const VERIFY_COMMENT: &str = "rustlife-verify";
+
+// Generations and hash a verification run expects, where known
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Expectation {
+    pub generations: Option<u64>,
+    pub hash: Option<u64>,
+}
+
+// Find the expectation stored in a pattern's comments, if there is one
+pub fn parse_expectation(comments: &[String]) -> Result<Expectation, String> {
+    let mut expectation = Expectation::default();
+    for comment in comments {
+        let mut words = comment.trim_start_matches('#').split_whitespace();
+        // Skip the comment type, like the `C` in `#C`
+        if words.next().map(|tag| tag.eq_ignore_ascii_case("C")) != Some(true)
+                || words.next() != Some(VERIFY_COMMENT) {
+            continue;
+        }
+
+        for word in words {
+            let mut parts = word.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some("generations"), Some(value)) => {
+                    expectation.generations = Some(value.parse()
+                        .map_err(|_| format!("Invalid generation count `{}`", value))?);
+                },
+                (Some("hash"), Some(value)) => expectation.hash = Some(parse_hash(value)?),
+                _ => return Err(format!("Unknown `{}` field `{}`", VERIFY_COMMENT, word)),
+            }
+        }
+    }
+    return Ok(expectation);
+}
+
+// Parse a canonical hash written as hex digits
+pub fn parse_hash(hash: &str) -> Result<u64, String> {
+    return u64::from_str_radix(hash.trim_start_matches("0x"), 16)
+        .map_err(|_| format!("Invalid hash `{}`", hash));
+}
+
+// Run a loaded pattern for `generations` and check its canonical hash against `expected`.
+// Prints the outcome and returns whether the hashes matched.
+pub fn verify(game: &mut GameOfLife, generations: u64, expected: Option<u64>) -> bool {
+    game.run(generations);
+    let hash = game.canonical_hash();
+    match expected {
+        Some(expected) if expected == hash => {
+            println!("OK: hash after {} generations is {:016x}", generations, hash);
+            return true;
+        },
+        Some(expected) => {
+            println!("MISMATCH: hash after {} generations is {:016x}, expected {:016x}",
+                generations, hash, expected);
+            return false;
+        },
+        None => {
+            println!("No expected hash given. To pin this result, add this line to the pattern:");
+            println!("#C {} generations={} hash={:016x}", VERIFY_COMMENT, generations, hash);
+            return false;
+        },
+    }
+}
+
+// Load the pattern at `input`, advance it `generations` and save the result to `output`, for
+// using rustlife in scripts. Nothing is printed unless `verbose` is set. A pattern bigger than
+// the board is an error rather than being silently cut off.
+pub fn step(game: &mut GameOfLife, input: &str, generations: u64, output: &str, verbose: bool)
+        -> Result<(), String> {
+    let header = game.load_rle(input)
+        .map_err(|e| format!("Couldn't load pattern `{}`: {}", input, e))?;
+    if header.width > game.state_width || header.height > game.state_height {
+        return Err(format!("Pattern `{}` is {}x{}, which doesn't fit on the {}x{} board. Pass a \
+            larger --width and --height.", input, header.width, header.height, game.state_width,
+            game.state_height));
+    }
+    if verbose {
+        println!("Loaded `{}` ({}x{}, rule {})", input, header.width, header.height, game.rule);
+    }
+
+    game.run(generations);
+    game.save_rle(output).map_err(|e| format!("Couldn't save `{}`: {}", output, e))?;
+    if verbose {
+        println!("Ran {} generations, leaving {} live cells", generations, game.live_cells().len());
+        println!("Saved `{}`", output);
+    }
+    return Ok(());
+}
+
+// Render the starting state and each of the next `generations` to numbered PNG files in `dir`,
+// drawing cells `scale` pixels across. Everything is drawn on the CPU, so no window or display
+// is needed.
+pub fn render_frames(game: &mut GameOfLife, dir: &str, generations: u64, scale: usize)
+        -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Couldn't create `{}`: {}", dir, e))?;
+    for frame in 0..=generations {
+        if frame > 0 {
+            game.update();
+        }
+        let (width, height, pixels) = png::render(&game.state, scale);
+        let path = std::path::Path::new(dir).join(format!("frame_{:05}.png", frame));
+        std::fs::write(&path, png::encode_rgb(width, height, &pixels))
+            .map_err(|e| format!("Couldn't save `{}`: {}", path.display(), e))?;
+    }
+    println!("Saved {} frames to `{}`", generations + 1, dir);
+    return Ok(());
+}
+
+// Record the starting state and the next `generations` as a delta stream, and save it to `path`
+// in a web page that plays it back
+pub fn export_html(game: &mut GameOfLife, path: &str, generations: u64) -> Result<(), String> {
+    let rule = game.rule.to_string();
+    let mut recorder = Recorder::new(Vec::new(), &game.state, &rule)
+        .map_err(|e| format!("Couldn't record the run: {}", e))?;
+    for _ in 0..generations {
+        game.update();
+        recorder.record(&game.state).map_err(|e| format!("Couldn't record the run: {}", e))?;
+    }
+    let title = format!("rustlife: {} for {} generations", rule, generations);
+    std::fs::write(path, html::encode(&recorder.into_inner(), &title))
+        .map_err(|e| format!("Couldn't save `{}`: {}", path, e))?;
+    println!("Saved {} generations to `{}`", generations, path);
+    return Ok(());
+}
+
+// Side of the random square each seed of the day grows from, and of the board it grows on
+const DAILY_SOUP_SIZE:  usize = 12;
+pub const DAILY_BOARD_SIZE: usize = 64;
+
+// Generations a daily soup gets to settle, and soups tried before giving up
+const DAILY_GENERATIONS: u64 = 2000;
+const DAILY_ATTEMPTS:    u64 = 200;
+
+// A seed of the day and how it was found
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Daily {
+    pub attempt: u64,  // Soups that died out or never settled before this one
+    pub period: usize,  // 1 for still lifes
+}
+
+// Get the number of days since 1970-01-01 in UTC, which seeds the pattern of the day
+pub fn today() -> i64 {
+    let seconds = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    return (seconds / 86400) as i64;
+}
+
+// Convert a number of days since 1970-01-01 to a (year, month, day) date
+pub fn civil_date(days: i64) -> (i64, u32, u32) {
+    // Count from 0000-03-01 in 400-year eras, so leap days fall at the end of each year
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096)
+        / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+    let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    return (year, month, day);
+}
+
+// Find the seed of the day: grow random soups from `day`, one after another, until one settles
+// into still lifes and oscillators without dying out. Soups grow on a small bounded board of
+// their own, so everyone with the same rule finds the same pattern on the same day whatever
+// their options, and what it settled into is centered on `game`.
+pub fn find_daily(game: &mut GameOfLife, day: i64) -> Option<Daily> {
+    // ChaCha's output is fixed by its seed, unlike StdRng's, which can change between versions
+    // of rand
+    let mut rng = ChaCha12Rng::seed_from_u64(day as u64);
+    let mut soup = GameOfLife::new(DAILY_BOARD_SIZE, DAILY_BOARD_SIZE, 0);
+    soup.rule = game.rule;
+    let corner = (DAILY_BOARD_SIZE - DAILY_SOUP_SIZE) / 2;
+    for attempt in 0..DAILY_ATTEMPTS {
+        soup.empty_state();
+        for x in corner..corner + DAILY_SOUP_SIZE {
+            for y in corner..corner + DAILY_SOUP_SIZE {
+                if rng.gen_bool(0.5) {
+                    soup.set_cell(x, y, true);
+                }
+            }
+        }
+
+        let mut detector = Detector::new();
+        for _ in 0..DAILY_GENERATIONS {
+            match detector.observe(&soup.state) {
+                Some(Event::Stable(period)) => {
+                    let cells = soup.live_cells();
+                    let pattern = Pattern { name: "Seed of the day".to_string(), cells: cells };
+                    game.empty_state();
+                    let offset = |side: usize| (side as i32 - DAILY_BOARD_SIZE as i32) / 2;
+                    game.stamp_pattern(&pattern, offset(game.state_width),
+                        offset(game.state_height));
+                    game.generation = soup.generation;
+                    return Some(Daily { attempt: attempt, period: period });
+                },
+                Some(Event::Extinct) => break,
+                None => soup.update(),
+            }
+        }
+    }
+    return None;
+}
+
+// How a pattern played out in a methuselah run
+#[derive(Clone, Debug, PartialEq)]
+pub struct Methuselah {
+    pub settled: Option<(u64, usize)>,  // First generation of the final cycle, and its period
+    pub extinct: bool,                  // Whether it settled by dying out
+    pub generations: u64,               // Generations run
+    pub peak: (u64, usize),             // Generation with the most live cells, and how many
+    pub population: usize,              // Population at the end
+    pub components: Vec<(String, usize)>,  // What it ended as, by name, most common first
+}
+
+impl Methuselah {
+    // Print the report
+    pub fn print(&self) {
+        match self.settled {
+            Some((generation, _)) if self.extinct => println!("Died out at generation {}",
+                generation),
+            Some((generation, 1)) => println!("Settled at generation {} into still lifes",
+                generation),
+            Some((generation, period)) => println!("Settled at generation {} with period {}",
+                generation, period),
+            None => println!("Still changing after {} generations", self.generations),
+        }
+        println!("Peak population {} at generation {}", self.peak.1, self.peak.0);
+        println!("Final population {} in {} components", self.population,
+            self.components.iter().map(|&(_, count)| count).sum::<usize>());
+        for (name, count) in &self.components {
+            println!("  {:>5} x {}", count, name);
+        }
+    }
+}
+
+// Run a game until it dies out or settles into a cycle of up to 60 generations, or for at most
+// `max_generations`, keeping track of its peak population. The components it ends as are named
+// where they're recognized, and otherwise listed by size.
+pub fn methuselah(game: &mut GameOfLife, max_generations: u64) -> Methuselah {
+    let mut detector = Detector::new();
+    let mut peak = (game.generation, game.population());
+    let start = game.generation;
+    let mut settled = None;
+    let mut extinct = false;
+    loop {
+        // The detector spots a repeat a period after the cycle started
+        match detector.observe(&game.state) {
+            Some(Event::Stable(period)) => {
+                settled = Some((game.generation - period as u64, period));
+                break;
+            },
+            Some(Event::Extinct) => {
+                settled = Some((game.generation - 1, 1));
+                extinct = true;
+                break;
+            },
+            None => {},
+        }
+        if game.generation - start >= max_generations {
+            break;
+        }
+        game.update();
+        let population = game.population();
+        if population > peak.1 {
+            peak = (game.generation, population);
+        }
+    }
+
+    // Tally what's left by name
+    let dictionary = Dictionary::known();
+    let (labels, count) = components::label(&game.state);
+    let mut cells = vec![Vec::new(); count];
+    for (x, y) in game.live_cells() {
+        cells[labels[x as usize][y as usize] as usize - 1].push((x, y));
+    }
+    let mut tally: Vec<(String, usize)> = Vec::new();
+    for component in cells {
+        let name = dictionary.name(&component).map_or_else(
+            || format!("unrecognized {}-cell object", component.len()), |name| name.to_string());
+        match tally.iter_mut().find(|(other, _)| *other == name) {
+            Some((_, count)) => *count += 1,
+            None => tally.push((name, 1)),
+        }
+    }
+    tally.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    return Methuselah {
+        settled: settled,
+        extinct: extinct,
+        generations: game.generation - start,
+        peak: peak,
+        population: game.population(),
+        components: tally,
+    };
+}
+
+// How a group of soups in a census played out: how many there were, and the totals their
+// averages come from
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Outcome {
+    pub soups: usize,
+    pub settled_at: u64,    // Sum of the generations they settled at
+    pub population: usize,  // Sum of their final populations
+}
+
+impl Outcome {
+    // Count another soup
+    fn add(&mut self, settled_at: u64, population: usize) {
+        self.soups += 1;
+        self.settled_at += settled_at;
+        self.population += population;
+    }
+}
+
+// What became of every soup in a census, grouped by how it ended
+#[derive(Clone, Debug, PartialEq)]
+pub struct Census {
+    pub extinct: Outcome,      // Died out
+    pub still: Outcome,        // Settled into still lifes
+    pub oscillating: Outcome,  // Settled into a cycle with at least one oscillator
+    pub unsettled: Outcome,    // Still changing when the generation limit was reached
+}
+
+impl Census {
+    // Count every group together
+    pub fn total(&self) -> Outcome {
+        let groups = [self.extinct, self.still, self.oscillating, self.unsettled];
+        return groups.iter().fold(Outcome::default(), |total, group| Outcome {
+            soups: total.soups + group.soups,
+            settled_at: total.settled_at + group.settled_at,
+            population: total.population + group.population,
+        });
+    }
+
+    // Print a table of the groups, with the fraction of soups in each, the average generation
+    // they settled at and their average final population. Unsettled soups weren't seen to
+    // settle, so they're left out of the average settling time, overall too.
+    pub fn print(&self) {
+        let total = self.total();
+        let settled = total.soups - self.unsettled.soups;
+        let settled_at = total.settled_at - self.unsettled.settled_at;
+        println!("{:<15} {:>7} {:>9} {:>12} {:>10}", "Outcome", "Soups", "Fraction",
+            "Avg. settled", "Avg. pop.");
+        let average = |sum: u64, count: usize| match count {
+            0 => "-".to_string(),
+            count => format!("{:.1}", sum as f64 / count as f64),
+        };
+        let rows = [("Died out", self.extinct), ("Still lifes", self.still),
+            ("Oscillators", self.oscillating)];
+        for &(name, group) in &rows {
+            print_census_row(name, group, total.soups, &average(group.settled_at, group.soups));
+        }
+        print_census_row("Still changing", self.unsettled, total.soups, "-");
+        print_census_row("All", total, total.soups, &average(settled_at, settled));
+    }
+}
+
+// Print one row of a census table
+fn print_census_row(name: &str, group: Outcome, soups: usize, settled_at: &str) {
+    let fraction = group.soups as f64 / soups.max(1) as f64 * 100.0;
+    let population = match group.soups {
+        0 => "-".to_string(),
+        count => format!("{:.1}", group.population as f64 / count as f64),
+    };
+    println!("{:<15} {:>7} {:>8.1}% {:>12} {:>10}", name, group.soups, fraction, settled_at,
+        population);
+}
+
+// Fill the board with `trials` random soups one after another, at the game's density and from
+// its generator, and run each until it settles or for at most `max_generations`, tallying how
+// they ended
+pub fn census(game: &mut GameOfLife, trials: u64, max_generations: u64) -> Census {
+    let mut census = Census {
+        extinct: Outcome::default(),
+        still: Outcome::default(),
+        oscillating: Outcome::default(),
+        unsettled: Outcome::default(),
+    };
+    for _ in 0..trials {
+        game.randomize_state();
+        let report = methuselah(game, max_generations);
+        let group = match report.settled {
+            Some(_) if report.extinct => &mut census.extinct,
+            Some((_, 1)) => &mut census.still,
+            Some(_) => &mut census.oscillating,
+            None => &mut census.unsettled,
+        };
+        let settled_at = report.settled.map_or(report.generations, |(generation, _)| generation);
+        group.add(settled_at, report.population);
+    }
+    return census;
+}
+
+// Hill-climb toward a target: flip a few random cells near the starting pattern, run the result
+// for `generations`, and keep the flips if the Hamming distance to `target` went down. Prints each
+// improvement and returns the best distance and the flipped cells it took, which the same `seed`
+// reproduces exactly.
+pub fn evolve_toward(start: &GameOfLife, target: &[Vec<bool>], generations: u64, steps: u64,
+        seed: u64) -> (usize, Vec<(usize, usize)>) {
+    let evaluate = |flips: &[(usize, usize)]| -> usize {
+        let mut game = start.clone();
+        for &(x, y) in flips {
+            let alive = game.state[x][y];
+            game.set_cell(x, y, !alive);
+        }
+        game.run(generations);
+        return hamming_distance(&game.state, target);
+    };
+
+    // Only flip cells around the starting pattern, where they can make a difference
+    let area = match start.bounding_box() {
+        Some(bounds) => {
+            let (x, y) = (bounds.x.saturating_sub(2), bounds.y.saturating_sub(2));
+            Rect {
+                x: x,
+                y: y,
+                width: (bounds.x + bounds.width + 2).min(start.state_width) - x,
+                height: (bounds.y + bounds.height + 2).min(start.state_height) - y,
+            }
+        },
+        None => Rect { x: 0, y: 0, width: start.state_width, height: start.state_height },
+    };
+
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    let mut best_flips: Vec<(usize, usize)> = Vec::new();
+    let mut best = evaluate(&best_flips);
+    println!("Step 0: distance {}", best);
+    for step in 1..=steps {
+        if best == 0 {
+            break;
+        }
+
+        // Toggle one to three cells. Flipping a cell that's already flipped undoes it.
+        let mut flips = best_flips.clone();
+        for _ in 0..rng.gen_range(1..=3) {
+            let cell = (area.x + rng.gen_range(0..area.width),
+                area.y + rng.gen_range(0..area.height));
+            match flips.iter().position(|&flip| flip == cell) {
+                Some(index) => { flips.remove(index); },
+                None => flips.push(cell),
+            }
+        }
+
+        let distance = evaluate(&flips);
+        if distance < best {
+            best = distance;
+            best_flips = flips;
+            println!("Step {}: distance {}", step, best);
+        }
+    }
+    best_flips.sort();
+    return (best, best_flips);
+}
+
+// How long a backend took and the live cells it ended with, or why it couldn't run
+type BenchResult = Result<(Duration, Vec<(i32, i32)>), String>;
+
+// Run the same starting state through every backend for `generations` and print a table of how
+// long each took and the canonical hash it ended on. Backends that can't represent the game's
+// configuration are skipped with the reason. The sparse backend shrinks its sets every
+// `gc_interval` generations. Returns whether every backend that ran agreed.
+pub fn bench_all(game: &GameOfLife, generations: u64, gc_interval: u64) -> bool {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let synchronous = game.update_mode == UpdateMode::Synchronous && game.previous.is_none()
+        && game.decay.is_none();
+
+    let mut results: Vec<(String, BenchResult)> = Vec::new();
+    {
+        let mut dense = game.clone();
+        let start = Instant::now();
+        dense.run(generations);
+        results.push(("dense-serial".to_string(), Ok((start.elapsed(), dense.live_cells()))));
+    }
+    {
+        let name = format!("dense-parallel (x{})", threads);
+        if synchronous {
+            let mut dense = game.clone();
+            let start = Instant::now();
+            for _ in 0..generations {
+                dense.update_parallel(threads);
+            }
+            results.push((name, Ok((start.elapsed(), dense.live_cells()))));
+        } else {
+            results.push((name, Err("only first-order synchronous updates without decay are \
+                supported".to_string())));
+        }
+    }
+    match SparseLife::from_game(game) {
+        Ok(mut sparse) => {
+            sparse.gc_interval = gc_interval;
+            let start = Instant::now();
+            for _ in 0..generations {
+                sparse.update();
+            }
+            results.push(("sparse".to_string(), Ok((start.elapsed(), sparse.live_cells()))));
+        },
+        Err(e) => results.push(("sparse".to_string(), Err(e))),
+    }
+
+    println!("{} generations on a {}x{} board, rule {}", generations, game.state_width,
+        game.state_height, game.rule);
+    println!("{:<28} {:>12} {:>14}  {:<16}", "backend", "time (ms)", "gens/sec", "hash");
+    let mut reference = None;
+    let mut agreed = true;
+    for (name, result) in results {
+        match result {
+            Ok((elapsed, cells)) => {
+                let hash = Pattern { name: String::new(), cells: cells }.canonical_hash();
+                let seconds = elapsed.as_secs_f64();
+                let rate = if seconds > 0.0 { generations as f64 / seconds } else { 0.0 };
+                let reference = *reference.get_or_insert(hash);
+                let verdict = if hash == reference { "" } else { "  MISMATCH" };
+                agreed &= hash == reference;
+                println!("{:<28} {:>12.1} {:>14.1}  {:016x}{}", name, seconds * 1000.0, rate, hash,
+                    verdict);
+            },
+            Err(reason) => println!("{:<28} skipped: {}", name, reason),
+        }
+    }
+    println!("{:<28} skipped: not implemented yet", "bitpacked");
+    println!("{:<28} skipped: not implemented yet", "hashlife");
+
+    if !agreed {
+        println!("Backends disagree on the final state");
+    }
+    return agreed;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_expectation_from_comments() {
+        let comments = vec![
+            "#N Glider".to_string(),
+            "#C rustlife-verify generations=40 hash=00000000deadbeef".to_string(),
+        ];
+        assert_eq!(parse_expectation(&comments), Ok(Expectation {
+            generations: Some(40),
+            hash: Some(0xdeadbeef),
+        }));
+        assert_eq!(parse_expectation(&[]), Ok(Expectation::default()));
+
+        let bad = vec!["#C rustlife-verify hash=xyz".to_string()];
+        assert!(parse_expectation(&bad).is_err());
+    }
+
+    #[test]
+    fn step_loads_advances_and_saves() {
+        let dir = std::env::temp_dir();
+        let input = dir.join("rustlife_step_test_in.rle");
+        let output = dir.join("rustlife_step_test_out.rle");
+        std::fs::write(&input, "x = 3, y = 1\n3o!\n").unwrap();
+
+        // A blinker is back where it started after two generations
+        let mut game = GameOfLife::new(5, 5, 0);
+        step(&mut game, input.to_str().unwrap(), 2, output.to_str().unwrap(), false).unwrap();
+        let mut reloaded = GameOfLife::new(5, 5, 0);
+        reloaded.load_rle(output.to_str().unwrap()).unwrap();
+        assert_eq!(reloaded.live_cells(), vec![(1, 2), (2, 2), (3, 2)]);
+
+        let mut small = GameOfLife::new(2, 2, 0);
+        let result = step(&mut small, input.to_str().unwrap(), 1, output.to_str().unwrap(), false);
+        assert!(result.is_err());
+        let _ = std::fs::remove_file(input);
+        let _ = std::fs::remove_file(output);
+    }
+
+    #[test]
+    fn evolve_toward_never_gets_worse() {
+        // Aim a lone block at the blinker it can't become by itself
+        let mut start = GameOfLife::new(12, 12, 0);
+        start.stamp_pattern(&::patterns::Pattern::from_rows("block", &["OO", "OO"]), 5, 5);
+        let mut target = GameOfLife::new(12, 12, 0);
+        target.stamp_pattern(&::patterns::Pattern::from_rows("blinker", &["OOO"]), 4, 6);
+        let initial = hamming_distance(&start.state, &target.state);
+
+        let (distance, flips) = evolve_toward(&start, &target.state, 2, 200, 1);
+        assert!(distance < initial);
+        assert_eq!(evolve_toward(&start, &target.state, 2, 200, 1), (distance, flips.clone()));
+
+        // The flips it reports give the distance it reports
+        let mut replay = start.clone();
+        for &(x, y) in &flips {
+            let alive = replay.state[x][y];
+            replay.set_cell(x, y, !alive);
+        }
+        replay.run(2);
+        assert_eq!(hamming_distance(&replay.state, &target.state), distance);
+    }
+
+    #[test]
+    fn methuselah_reports_settling_peak_and_what_is_left() {
+        // Three cells of a block fill in the fourth, and a blinker keeps blinking alongside
+        let mut game = GameOfLife::new(16, 16, 0);
+        game.stamp_pattern(&Pattern::from_rows("pre-block", &["OO", "O."]), 2, 2);
+        game.stamp_pattern(&Pattern::from_rows("blinker", &["OOO"]), 9, 9);
+        let report = methuselah(&mut game, 100);
+        assert_eq!(report.settled, Some((1, 2)));
+        assert!(!report.extinct);
+        assert_eq!(report.peak, (1, 7));
+        assert_eq!(report.population, 7);
+        assert_eq!(report.components, vec![("blinker".to_string(), 1), ("block".to_string(), 1)]);
+
+        // A pair of cells dies straight away
+        let mut game = GameOfLife::new(8, 8, 0);
+        game.stamp_pattern(&Pattern::from_rows("pair", &["OO"]), 3, 3);
+        let report = methuselah(&mut game, 100);
+        assert_eq!((report.settled, report.extinct), (Some((1, 1)), true));
+        assert_eq!(report.peak, (0, 2));
+
+        // A glider on a torus takes longer than the cap to come back around
+        let mut game = GameOfLife::new(24, 24, 0);
+        game.topology = ::Topology::Torus;
+        game.stamp_pattern(&::patterns::glider(), 2, 2);
+        let report = methuselah(&mut game, 40);
+        assert_eq!((report.settled, report.generations), (None, 40));
+        assert_eq!(report.components, vec![("glider".to_string(), 1)]);
+    }
+
+    #[test]
+    fn census_accounts_for_every_soup() {
+        let mut game = GameOfLife::new(16, 16, 5);
+        let tally = census(&mut game, 12, 500);
+        let total = tally.total();
+        assert_eq!(total.soups, 12);
+        assert_eq!(total.population, tally.still.population + tally.oscillating.population
+            + tally.unsettled.population);
+        assert_eq!(tally.extinct.population, 0);
+
+        // Empty soups die out at once
+        game.density = 0.0;
+        let tally = census(&mut game, 3, 500);
+        assert_eq!(tally.extinct, Outcome { soups: 3, settled_at: 0, population: 0 });
+    }
+
+    #[test]
+    fn render_frames_writes_one_png_per_generation() {
+        let dir = std::env::temp_dir().join(format!("rustlife_frames_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        let mut game = GameOfLife::new(8, 8, 0);
+        game.stamp_pattern(&::patterns::glider(), 2, 2);
+        render_frames(&mut game, dir, 3, 2).unwrap();
+        let mut frames: Vec<String> = std::fs::read_dir(dir).unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        frames.sort();
+        assert_eq!(frames, ["frame_00000.png", "frame_00001.png", "frame_00002.png",
+            "frame_00003.png"]);
+        assert_eq!(game.generation, 3);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_date(0), (1970, 1, 1));
+        assert_eq!(civil_date(-1), (1969, 12, 31));
+        assert_eq!(civil_date(11016), (2000, 2, 29));
+        assert_eq!(civil_date(20739), (2026, 10, 13));
+    }
+
+    #[test]
+    fn daily_pattern_is_the_same_all_day() {
+        let mut game = GameOfLife::new(64, 64, 0);
+        let daily = find_daily(&mut game, 20000).unwrap();
+        assert!(game.population() > 0);
+        let mut again = GameOfLife::new(80, 64, 0);
+        assert_eq!(find_daily(&mut again, 20000), Some(daily));
+        assert_eq!(again.canonical_hash(), game.canonical_hash());
+
+        // Whatever it settled into repeats with the period it reports
+        let settled = game.state.clone();
+        game.run(daily.period as u64);
+        assert_eq!(game.state, settled);
+    }
+
+    #[test]
+    fn backends_agree() {
+        let mut game = GameOfLife::new(40, 30, 11);
+        game.randomize_state();
+        assert!(bench_all(&game, 20, ::sparse::DEFAULT_GC_INTERVAL));
+    }
+
+    #[test]
+    fn verify_matches_evolved_hash() {
+        // A glider is the same shape every 4 generations, wherever it's moved to
+        let mut game = GameOfLife::new(20, 20, 0);
+        game.stamp_pattern(&::patterns::glider(), 2, 2);
+        let hash = game.canonical_hash();
+        assert!(verify(&mut game, 8, Some(hash)));
+        assert!(!verify(&mut game, 1, Some(hash)));
+    }
+}
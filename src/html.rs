@@ -0,0 +1,58 @@
+/* ##############################################
+# HTML export: a delta stream embedded in a     #
+# single page with a small canvas player, so a  #
+# run can be shared and replayed in a browser.  #
+############################################## */
+
+// The player page, with `{{TITLE}}` and `{{DELTAS}}` left to fill in
+const PLAYER: &str = include_str!("player.html");
+
+// Digits of base64, in order
+const BASE64_DIGITS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Write a delta stream, as from `deltas::Recorder`, into a page that plays it back
+pub fn encode(stream: &[u8], title: &str) -> String {
+    return PLAYER.replace("{{TITLE}}", &escape(title)).replace("{{DELTAS}}", &base64(stream));
+}
+
+// Escape text for use in HTML
+fn escape(text: &str) -> String {
+    return text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+}
+
+// Encode bytes as base64, padded with `=`, which JavaScript's `atob` reads back
+fn base64(data: &[u8]) -> String {
+    let mut output = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bits = (chunk[0] as u32) << 16
+            | (chunk.get(1).cloned().unwrap_or(0) as u32) << 8
+            | chunk.get(2).cloned().unwrap_or(0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(BASE64_DIGITS[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    return output;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embeds_the_stream_in_the_player() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+
+        let page = encode(b"RLDELTA1", "B3/S23 <run>");
+        assert!(page.contains("var DELTAS = \"UkxERUxUQTE=\";"));
+        assert!(page.contains("<title>B3/S23 &lt;run&gt;</title>"));
+        assert!(!page.contains("{{"));
+    }
+}
@@ -1,22 +1,237 @@
+// The code base favours explicit `return`s and indexed grid loops
+#![allow(clippy::needless_return, clippy::needless_range_loop, clippy::redundant_field_names)]
+
 extern crate clap;
 extern crate olc_pixel_game_engine;
 extern crate rand;
+extern crate rand_chacha;
+
+mod camera;
+mod checkpoint;
+mod components;
+mod coupled;
+mod decay;
+mod deltas;
+mod dialog;
+mod eden;
+mod effects;
+mod explorer;
+mod headless;
+mod html;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod minimap;
+mod patterns;
+mod plaintext;
+mod png;
+mod rle;
+mod rule;
+mod sparse;
+mod stats;
+mod svg;
+mod window;
 
 use crate::olc_pixel_game_engine as olc;
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+use camera::Camera;
+use rule::Rule;
+use rand::{Rng, SeedableRng};
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha12Rng;
 
 // Screen constants
 const SCREEN_WIDTH:  i32 = 200;
 const SCREEN_HEIGHT: i32 = 200;
 const SCREEN_SCALE:  i32 = 4;
 
+// Fraction of cells that start alive in a random state
+const DENSITY: f64 = 0.5;
+
+// Generations --verify runs for when neither the pattern nor the command line say
+const VERIFY_GENERATIONS: u64 = 100;
+
+// Generations --bench-all runs for by default
+const BENCH_GENERATIONS: u64 = 500;
+
+// Generations --headless-render saves by default, after the starting state
+const RENDER_GENERATIONS: u64 = 100;
+
+// Generations --export-html records by default, after the starting state
+const HTML_GENERATIONS: u64 = 200;
+
+// Most generations each --census soup runs for by default
+const CENSUS_GENERATIONS: u64 = 5000;
+
+// Most generations --methuselah runs for by default, waiting for the pattern to settle
+const METHUSELAH_GENERATIONS: u64 = 50000;
+
+// Generations each --evolve-toward candidate runs for, and how many candidates it tries, by default
+const EVOLVE_GENERATIONS: u64 = 50;
+const EVOLVE_STEPS:       u64 = 1000;
+
+// Where F5 saves a checkpoint and F9 restores it from
+const CHECKPOINT_PATH: &str = "rustlife_checkpoint.rle";
+
+// Directory of the user's own patterns, loaded if it exists and --patterns-dir isn't given
+const PATTERNS_DIR: &str = "rustlife_patterns";
+
+// Where the board is dumped if the application panics
+const CRASH_FILE: &str = "rustlife_crash.rle";
+
+// How many edits can be undone
+const UNDO_LIMIT: usize = 64;
+
+// Generations between estimates of the board's entropy, which looks at every cell
+const ENTROPY_INTERVAL: u64 = 8;
+
 // How long to wait between updates
 const UPDATE_TIME: f32 = 1.0 / 15.0;  // 15 FPS
 
+// Most generations run in a single frame to catch up with the requested rate. Past this, the
+// simulation falls behind rather than stalling the window.
+const MAX_UPDATES_PER_FRAME: u32 = 64;
+
+// Time each frame may spend simulating, in milliseconds, by default
+const TICK_BUDGET_MS: u64 = 8;
+
+// Fraction of the requested rate below which the HUD shows the actual rate in red
+const RATE_SHORTFALL: f32 = 0.9;
+
 // Key bindings
 const KEY_STEP:        olc::Key = olc::Key::S;
 const KEY_STEP_TOGGLE: olc::Key = olc::Key::SPACE;
+const KEY_STEP_BACK:   olc::Key = olc::Key::BACK;
+const KEY_JOG:         olc::Key = olc::Key::J;
 const KEY_RESET:       olc::Key = olc::Key::R;
 const KEY_EMPTY:       olc::Key = olc::Key::E;
+const KEY_INSPECT:     olc::Key = olc::Key::N;
+const KEY_SAVE_SVG:    olc::Key = olc::Key::V;
+const KEY_SAVE_AS:     olc::Key = olc::Key::W;
+const KEY_SAVE_PNG:    olc::Key = olc::Key::B;
+const KEY_CHECKPOINT:  olc::Key = olc::Key::F5;
+const KEY_RESTORE:     olc::Key = olc::Key::F9;
+const KEY_SOFT_RESET:  olc::Key = olc::Key::F8;
+const KEY_UNTOUCHED:   olc::Key = olc::Key::U;
+const KEY_GLIDER:      olc::Key = olc::Key::G;
+const KEY_PLACE:       olc::Key = olc::Key::P;
+const KEY_ROTATE:      olc::Key = olc::Key::D;
+const KEY_TOOL_TOGGLE:  olc::Key = olc::Key::K1;
+const KEY_TOOL_PAINT:   olc::Key = olc::Key::K2;
+const KEY_TOOL_ERASE:   olc::Key = olc::Key::K3;
+const KEY_TOOL_STAMP:   olc::Key = olc::Key::K4;
+const KEY_TOOL_INSPECT: olc::Key = olc::Key::K5;
+const KEY_TOOL_REGION:  olc::Key = olc::Key::K6;
+const KEY_LEGEND:      olc::Key = olc::Key::L;
+const KEY_NEXT_RULE:   olc::Key = olc::Key::K;
+const KEY_TRAILS:      olc::Key = olc::Key::T;
+const KEY_FLASH:       olc::Key = olc::Key::F;
+const KEY_CROP:        olc::Key = olc::Key::C;
+const KEY_MINIMAP:     olc::Key = olc::Key::M;
+const KEY_CROSSHAIR:   olc::Key = olc::Key::X;
+const KEY_COMPONENTS:  olc::Key = olc::Key::O;
+const KEY_IDENTIFY:    olc::Key = olc::Key::I;
+const KEY_HEAT:        olc::Key = olc::Key::A;
+const KEY_EXPLORE:     olc::Key = olc::Key::Y;
+const KEY_NUDGE_LEFT:  olc::Key = olc::Key::LEFT;
+const KEY_NUDGE_RIGHT: olc::Key = olc::Key::RIGHT;
+const KEY_NUDGE_UP:    olc::Key = olc::Key::UP;
+const KEY_NUDGE_DOWN:  olc::Key = olc::Key::DOWN;
+const KEY_SCROLL_ARENA: olc::Key = olc::Key::CTRL;  // Held with the arrow keys
+const KEY_UNDO:        olc::Key = olc::Key::Z;
+const KEY_HUD:         olc::Key = olc::Key::H;
+const KEY_ENTROPY:     olc::Key = olc::Key::F2;
+const KEY_HUD_CORNER:  olc::Key = olc::Key::F3;
+const KEY_SCALE_BAR:   olc::Key = olc::Key::F4;
+const KEY_COMPARE:     olc::Key = olc::Key::F6;
+const KEY_BIRTH_COLORS: olc::Key = olc::Key::F7;
+const KEY_CLEAR_LIMIT: olc::Key = olc::Key::Q;
+const KEY_NEXT_COMPONENT: olc::Key = olc::Key::TAB;
+const KEY_UNFRAME:        olc::Key = olc::Key::ESCAPE;
+
+// Rule legend colors
+const LEGEND_ON_COLOR:  olc::Pixel = olc::DARK_GREEN;
+const LEGEND_OFF_COLOR: olc::Pixel = olc::VERY_DARK_GREY;
+
+// Size of each box in the rule legend, and the height of the whole legend: a line of text over
+// a row of boxes each for birth and survival
+const LEGEND_BOX_SIZE: i32 = 9;
+const LEGEND_HEIGHT:   i32 = 2 * LEGEND_BOX_SIZE + 11;
+
+// Pixels each line of HUD text takes up, and the width of each character
+const TEXT_LINE_HEIGHT: i32 = 10;
+const TEXT_CHAR_WIDTH:  i32 = 8;
+
+// Colors for the second layer in --coupled mode, where it's alone and where it overlaps the first
+const PARTNER_COLOR: olc::Pixel = olc::Pixel::rgb(255, 128, 0);
+const OVERLAP_COLOR: olc::Pixel = olc::YELLOW;
+
+// Color of the lines through the pointer and their coordinate label
+const CROSSHAIR_COLOR: olc::Pixel = olc::Pixel::rgb(0, 160, 160);
+
+// Outline of the arena, when the simulation is limited to one
+const ARENA_COLOR: olc::Pixel = olc::DARK_GREEN;
+
+// Outline and rule of each region with a rule of its own
+const REGION_COLOR: olc::Pixel = olc::Pixel::rgb(160, 96, 255);
+
+// Smallest cells, in pixels across, that are drawn with a bevel
+const MIN_BEVEL_ZOOM: i32 = 3;
+
+// Largest cells, in pixels across, that framing a component zooms in to
+const MAX_FRAME_ZOOM: i32 = 16;
+
+// Outline of the pattern that clicking places
+const PLACE_PREVIEW_COLOR: olc::Pixel = olc::Pixel::rgb(0, 96, 160);
+
+// Largest brush the paint and erase tools can have, in cells across
+const MAX_BRUSH_SIZE: i32 = 32;
+
+// Names of recognized patterns
+const LABEL_COLOR: olc::Pixel = olc::Pixel::rgb(255, 160, 0);
+
+// Name of the active tool
+const TOOL_COLOR: olc::Pixel = olc::CYAN;
+
+// Longest the scale bar can be, in pixels, and the box drawn behind it
+const SCALE_BAR_MAX_LENGTH: i32 = 48;
+const SCALE_BAR_BACKGROUND: olc::Pixel = olc::VERY_DARK_GREY;
+
+// Outline of the component the camera is framed on
+const FRAME_COLOR: olc::Pixel = olc::Pixel::rgb(255, 200, 0);
+
+// Cells the arena moves per press of CTRL and an arrow key, without and with SHIFT held
+const ARENA_SCROLL_STEP:      i32 = 1;
+const ARENA_SCROLL_FAST_STEP: i32 = 10;
+
+// Generations it takes the birth colors to go all the way around the hues
+const BIRTH_HUE_PERIOD: u64 = 48;
+
+// Birth generation of cells that aren't alive, or haven't been seen alive yet
+const NOT_BORN: u64 = u64::MAX;
+
+// Tint for cells that have never been alive
+const UNTOUCHED_COLOR: olc::Pixel = olc::Pixel::rgb(0, 0, 48);
+
+// Background of dead cells by live neighbor count, from cold to hot
+const HEAT_COLORS: [olc::Pixel; 9] = [
+    olc::Pixel::rgb(0, 0, 0),
+    olc::Pixel::rgb(0, 0, 72),
+    olc::Pixel::rgb(0, 32, 128),
+    olc::Pixel::rgb(0, 96, 128),
+    olc::Pixel::rgb(64, 128, 0),
+    olc::Pixel::rgb(160, 128, 0),
+    olc::Pixel::rgb(192, 64, 0),
+    olc::Pixel::rgb(208, 0, 0),
+    olc::Pixel::rgb(255, 0, 128),
+];
+
+// Cells that are only in the first or only in the second pattern, under --diff
+const DIFF_FIRST_COLOR:  olc::Pixel = olc::Pixel::rgb(255, 64, 64);
+const DIFF_SECOND_COLOR: olc::Pixel = olc::Pixel::rgb(64, 160, 255);
+
+// Line between the board and its copy, and the copy's caption
+const COMPARISON_COLOR: olc::Pixel = olc::Pixel::rgb(128, 128, 128);
 
 /* ##########################################
 # The main application structure.           #
@@ -24,233 +239,3430 @@ const KEY_EMPTY:       olc::Key = olc::Key::E;
 ########################################## */
 struct Application {
     game:           GameOfLife,
+    camera:         Camera,
     update_counter: f32,
     update_delta:   f32,
+    rate_meter:     stats::RateMeter,  // Generations actually run per second
+    tick_budget:    std::time::Duration,  // Longest a frame spends simulating before drawing
+    frame_limiter:  Option<stats::FrameLimiter>,  // Holds frames to a steady rate, in --fps-cap
     step:           bool,  // Whether program should run automatically or be manually stepped
+    show_inspector: bool,  // Whether to show the coordinates and neighbor count of the hovered cell
+    show_legend:    bool,  // Whether to show what the rule does for each neighbor count
+    show_minimap:   bool,  // Whether to show an overview of the whole board
+    show_crosshair: bool,  // Whether to draw lines across the screen through the pointer
+    show_hud:       bool,  // Whether to show the generation, population and activity
+    show_entropy:   bool,  // Whether to show the entropy estimate
+    hud_corner:     Corner,  // Where the HUD and other text readouts are drawn
+    show_scale_bar: bool,  // Whether to show how many cells a length on screen covers
+    capture_view:   bool,  // Whether to save the view once this frame is drawn
+    log_entropy:    bool,  // Whether the population log has an entropy column, in --log-entropy
+    entropy:        Option<f64>,  // Latest estimate, in bits per block, while shown or logged
+    bevel:          bool,  // Whether to give live cells lit and shaded edges
+    max_generation: Option<u64>,  // Generation to pause at, in --max-gen
+    diff_against:   Option<Vec<Vec<bool>>>,  // Second pattern to compare the board with, in --diff
+    comparison:     Option<GameOfLife>,  // Copy of the board running alongside it, in its own pane
+    framing:        Option<Framing>,  // Component the camera is zoomed in on
+    save_dialog:    Option<dialog::SaveDialog>,  // Open while a filename is being typed
+    explorer:       Option<explorer::Explorer>,  // Takes over the screen while exploring rules
+    tool:           Tool,  // What clicking on the board does
+    library:        Vec<patterns::Pattern>,  // Patterns the stamp tool cycles through
+    brush_size:     i32,   // Cells across the square the paint and erase tools cover
+    region_start:   Option<(i32, i32)>,  // Cell a region is being dragged out from
+    svg_style:      svg::SvgStyle,
+    draw_options:   DrawOptions,
+    effects:        effects::Effects,
+    coupling:       Option<coupled::Coupling>,  // Second layer, in --coupled mode
+    components:     Option<components::Tracker>,  // Colors clusters of cells when enabled
+    dictionary:     Option<patterns::Dictionary>,  // Labels known patterns when enabled
+    goe_hint:       Option<eden::Hint>,  // Highlights patches with no predecessor, in --goe-hint
+    undo_stack:     Vec<Vec<Vec<bool>>>,  // States from before recent edits, newest last
+    base:           Option<checkpoint::Base>,  // Last checkpoint saved or restored, for F8
+    activity:       stats::Activity,  // Cells changed by recent generations
+    notifier:       Option<stats::Detector>,  // Rings the bell on events, in --notify
+    population_log: Option<std::io::BufWriter<std::fs::File>>,  // --population-csv
+    crash_handle:   Option<CrashHandle>,  // Copy of the game kept for the panic hook
+    #[cfg(feature = "metrics")]
+    metrics:        Option<metrics::Handle>,  // Counters served over HTTP, in --metrics-port
+    recorder:       Option<deltas::Recorder<std::io::BufWriter<std::fs::File>>>,  // --record-deltas
+    playback:       Option<deltas::Player<std::io::BufReader<std::fs::File>>>,    // --play-deltas
+    placement:      window::Placement,  // Where the window goes and whether it has borders
 }
 
 impl Application {
     fn new(game: GameOfLife) -> Self {
         Application {
             game: game,
+            camera: Camera::new(),
             update_counter: 0.0,
             update_delta: UPDATE_TIME,
+            rate_meter: stats::RateMeter::new(),
+            tick_budget: std::time::Duration::from_millis(TICK_BUDGET_MS),
+            frame_limiter: None,
             step: false,
+            show_inspector: false,
+            show_legend: false,
+            show_minimap: false,
+            show_crosshair: false,
+            show_hud: false,
+            show_entropy: false,
+            hud_corner: Corner::TopLeft,
+            show_scale_bar: false,
+            capture_view: false,
+            log_entropy: false,
+            entropy: None,
+            bevel: false,
+            max_generation: None,
+            diff_against: None,
+            comparison: None,
+            framing: None,
+            save_dialog: None,
+            explorer: None,
+            tool: Tool::Toggle,
+            library: patterns::library(),
+            brush_size: 1,
+            region_start: None,
+            svg_style: svg::SvgStyle::default(),
+            draw_options: DrawOptions::default(),
+            effects: effects::Effects::new(),
+            coupling: None,
+            components: None,
+            dictionary: None,
+            goe_hint: None,
+            undo_stack: Vec::new(),
+            base: None,
+            activity: stats::Activity::new(),
+            notifier: None,
+            population_log: None,
+            crash_handle: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            recorder: None,
+            playback: None,
+            placement: window::Placement::default(),
         }
     }
 }
 
-impl olc::Application for Application {
-    // Called on application creation and destruction respectively
-    fn on_user_create(&mut self) -> Result<(), olc::Error> { Ok(()) }
-    fn on_user_destroy(&mut self) -> Result<(), olc::Error> { Ok(()) }
+// A component the camera has been framed on, picked from the board as it was at the time
+struct Framing {
+    index:  usize,   // Which component, in the order they were labelled
+    count:  usize,   // Number of components on the board
+    bounds: Rect,
+    cells:  usize,
+    camera: Camera,  // Camera to go back to when done
+}
 
-    // Called every frame
-    fn on_user_update(&mut self, elapsed_time: f32) -> Result<(), olc::Error> {
-        // Handle frame advance
-        if self.step {
-            // Advance frame on keypress
-            if olc::get_key(KEY_STEP).pressed {
-                self.game.update();
+// A library pattern armed for placing, and how it's turned
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Placement {
+    index:         usize,  // Position in the application's pattern library
+    quarter_turns: u32,    // Clockwise
+}
+
+impl Placement {
+    // Get the pattern as it will be stamped
+    fn pattern(&self, library: &[patterns::Pattern]) -> patterns::Pattern {
+        return library[self.index].rotated_by(self.quarter_turns);
+    }
+
+    // Get the position of the pattern's top-left corner that centers it on a cell
+    fn corner(&self, pattern: &patterns::Pattern, x: i32, y: i32) -> (i32, i32) {
+        return (x - pattern.width() / 2, y - pattern.height() / 2);
+    }
+}
+
+// What clicking on the board does
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Tool {
+    Toggle,            // Flip the clicked cell
+    Paint,             // Bring cells to life while dragging
+    Erase,             // Kill cells while dragging
+    Stamp(Placement),  // Stamp a library pattern on every click
+    Inspect,           // Print the clicked cell's state and neighbor count
+    Region(Rule),      // Drag out a region that follows this rule
+}
+
+impl Tool {
+    // Get the name shown in the mode indicator
+    fn name(&self, library: &[patterns::Pattern]) -> String {
+        return match self {
+            Tool::Toggle  => "Toggle".to_string(),
+            Tool::Paint   => "Paint".to_string(),
+            Tool::Erase   => "Erase".to_string(),
+            Tool::Stamp(placing) => format!("Stamp {} ({} deg)",
+                library[placing.index].name, placing.quarter_turns * 90),
+            Tool::Inspect => "Inspect".to_string(),
+            Tool::Region(rule) => format!("Region {}", rule),
+        };
+    }
+}
+
+impl Application {
+    // Advance the simulation by one generation, measuring how much it changed
+    fn update_game(&mut self) {
+        let previous = self.game.state.clone();
+        let generation = self.game.generation;
+        self.advance_game();
+        if self.game.generation == generation {
+            return;
+        }
+
+        // Several generations can run in one frame, so the limit can be passed over
+        if self.max_generation.is_some_and(|limit| self.game.generation >= limit) {
+            println!("Paused at the generation limit of {}", self.game.generation);
+            self.step = true;
+        }
+
+        let changed = stats::hamming_distance(&previous, &self.game.state);
+        self.activity.record(changed);
+        #[cfg(feature = "metrics")]
+        self.publish_metrics();
+        let state = &self.game.state;
+        if let Some(event) = self.notifier.as_mut().and_then(|notifier| notifier.observe(state)) {
+            notify(event, self.game.generation);
+        }
+        // Only estimated every few generations, since it looks at the whole board
+        let estimated = (self.show_entropy || self.log_entropy)
+            && self.game.generation.is_multiple_of(ENTROPY_INTERVAL);
+        if estimated {
+            self.entropy = Some(stats::block_entropy(&self.game.state));
+        }
+        if let Some(log) = &mut self.population_log {
+            let population = self.game.population();
+            let entropy = match self.entropy {
+                _ if !self.log_entropy => String::new(),
+                Some(entropy) if estimated => format!(",{:.4}", entropy),
+                _ => ",".to_string(),
+            };
+            if let Err(e) = writeln!(log, "{},{},{}{}", self.game.generation, population, changed,
+                    entropy) {
+                eprintln!("ERROR: Stopped logging the population: {}", e);
+                self.population_log = None;
             }
-        } else {
-            // Limit to defined updates per second
-            self.update_counter += elapsed_time;
-            if self.update_counter >= self.update_delta {
-                self.game.update();
-                self.update_counter = 0.0;
+        }
+    }
+
+    // Advance the simulation by one generation, from the recording when playing one back
+    fn advance_game(&mut self) {
+        if let Some(player) = &mut self.playback {
+            // Play the next generation of the recording instead of simulating it
+            match player.next_frame() {
+                Ok(Some((births, deaths))) => self.game.apply_delta(&births, &deaths),
+                Ok(None) => {
+                    println!("Playback finished at generation {}", self.game.generation);
+                    self.playback = None;
+                    self.step = true;
+                },
+                Err(e) => {
+                    eprintln!("ERROR: Couldn't read the rest of the recording: {}", e);
+                    self.playback = None;
+                    self.step = true;
+                },
             }
+            return;
         }
 
-        // Input handling
-        if olc::get_key(KEY_EMPTY).pressed {
-            // Reset with empty state
-            self.game.empty_state();
-            self.step = true;
-        } else if olc::get_key(KEY_RESET).pressed {
-            // Reset with random state
-            self.game.randomize_state();
-        } else if olc::get_key(KEY_STEP_TOGGLE).pressed {
-            // Toggle step mode
-            self.step = !self.step;
-            self.update_counter = 0.0;
+        match self.coupling {
+            Some(ref mut coupling) => coupling.update(&mut self.game),
+            None => self.game.update(),
+        }
+        if let Some(comparison) = &mut self.comparison {
+            comparison.update();
+        }
+
+        let state = &self.game.state;
+        let recorded = self.recorder.as_mut().map(|recorder| recorder.record(state));
+        if let Some(Err(e)) = recorded {
+            eprintln!("ERROR: Stopped recording deltas: {}", e);
+            self.recorder = None;
         }
+    }
+
+    // Wait until the frame is due under --fps-cap. The time spent waiting counts towards the next
+    // frame's elapsed time, so the simulation keeps to its own rate.
+    fn limit_frame_rate(&mut self) {
+        if let Some(limiter) = &mut self.frame_limiter {
+            std::thread::sleep(limiter.delay(std::time::Instant::now()));
+        }
+    }
+
+    // Hand the latest generation's counters to the metrics server. Skip it rather than wait if
+    // a scrape has the snapshot.
+    #[cfg(feature = "metrics")]
+    fn publish_metrics(&self) {
+        if let Some(handle) = &self.metrics {
+            if let Ok(mut snapshot) = handle.try_lock() {
+                snapshot.record(self.game.generation, self.game.population());
+                snapshot.updates_per_second = self.rate_meter.rate;
+            }
+        }
+    }
 
-        // Click to toggle a cell
-        if olc::get_mouse(0).pressed {
-            let x = olc::get_mouse_x() as usize;
-            let y = olc::get_mouse_y() as usize;
-            self.game.state[x][y] = !self.game.state[x][y];
+    // Keep a scrolled arena in view, recentering on it once any of it goes off the screen
+    fn follow_arena(&mut self, arena: Rect) {
+        let (screen_width, screen_height) = (olc::screen_width(), olc::screen_height());
+        let (left, top) = self.camera.screen_position(arena.x as i32, arena.y as i32);
+        let (right, bottom) = self.camera.screen_position((arena.x + arena.width) as i32,
+            (arena.y + arena.height) as i32);
+        if left < 0 || top < 0 || right > screen_width || bottom > screen_height {
+            self.camera.center_on((arena.x + arena.width / 2) as i32,
+                (arena.y + arena.height / 2) as i32, screen_width, screen_height);
         }
+    }
 
-        self.game.draw();
+    // Copy the board into a second pane that runs alongside it, or close the pane if it's open.
+    // Returns why it can't be opened, for modes where the copy couldn't keep up.
+    fn toggle_comparison(&mut self) -> Result<(), String> {
+        if self.comparison.take().is_some() {
+            return Ok(());
+        }
+        if self.coupling.is_some() {
+            return Err("The comparison pane can't copy the second layer of --coupled".to_string());
+        }
+        if self.playback.is_some() || self.diff_against.is_some() {
+            return Err("The comparison pane only copies boards that are being simulated"
+                .to_string());
+        }
+        self.comparison = Some(self.game.clone());
         return Ok(());
     }
-}
 
-/* ################################################
-# Conway's Game of Life                           #
-# Handles updating and drawing of the game state. #
-################################################ */
-struct GameOfLife {
-    state: Vec<Vec<bool>>,
-    state_width: usize,
-    state_height: usize,
-    live_threshold: u8,
-    die_threshold_lower: u8,
-    die_threshold_upper: u8,
-}
+    // Get where the comparison pane starts on the screen, if it's open. It takes the right half.
+    fn comparison_left(&self) -> Option<i32> {
+        return self.comparison.as_ref().map(|_| olc::screen_width() / 2);
+    }
 
-impl GameOfLife {
-    // Create a new game structure with a given width and height
-    fn new(width: usize, height: usize) -> Self {
-        return GameOfLife {
-            state: vec![vec![false; height]; width],
-            state_width: width,
-            state_height: height,
-            live_threshold: 3,
-            die_threshold_lower: 2,
-            die_threshold_upper: 3,
+    // Draw the comparison pane over the right half of the screen, showing the same cells of the
+    // copy as the left half shows of the board
+    fn draw_comparison(&self) -> Result<(), olc::Error> {
+        let (comparison, left) = match (&self.comparison, self.comparison_left()) {
+            (Some(comparison), Some(left)) => (comparison, left),
+            _ => return Ok(()),
         };
+        let (screen_width, screen_height) = (olc::screen_width(), olc::screen_height());
+        olc::fill_rect(left, 0, screen_width - left, screen_height, olc::BLACK);
+        let camera = Camera { x: self.camera.x + left, ..self.camera };
+        for (x, y) in comparison.live_cells() {
+            camera.draw_cell(x as usize, y as usize, olc::WHITE);
+        }
+        olc::draw_line(left, 0, left, screen_height - 1, COMPARISON_COLOR);
+
+        // Cropping the board leaves nothing to line the copy up against
+        let mut caption = format!("Copy: {}", comparison.rule);
+        if comparison.state_width == self.game.state_width
+                && comparison.state_height == self.game.state_height {
+            let differ = stats::hamming_distance(&comparison.state, &self.game.state);
+            caption += &format!(", {} differ", differ);
+        }
+        olc::draw_string(left + 2, screen_height - 9, &caption, COMPARISON_COLOR)?;
+        return Ok(());
     }
 
-    // Update the game state
-    fn update(&mut self) {
-        let mut new_state = self.state.clone();
-        for y in 0..self.state_height {
-            for x in 0..self.state_width {
-                let neighbors = self.cell_get_neighbors(x as i32, y as i32);
-                if self.state[x][y]
-                        && (neighbors < self.die_threshold_lower
-                        || neighbors > self.die_threshold_upper) {
-                    // Kill cell if above or below bounds
-                    new_state[x][y] = false;
-                } else if !self.state[x][y] && neighbors == self.live_threshold {
-                    // Create cell if neighbors are exactly at threshold
-                    new_state[x][y] = true;
+    // Draw the second layer over the first in --coupled mode
+    fn draw_partner(&self) {
+        if let Some(coupling) = &self.coupling {
+            let partner = &coupling.partner;
+            for y in 0..partner.state_height {
+                for x in 0..partner.state_width {
+                    if partner.state[x][y] {
+                        let color =
+                            if self.game.state[x][y] { OVERLAP_COLOR } else { PARTNER_COLOR };
+                        self.camera.draw_cell(x, y, color);
+                    }
                 }
             }
         }
+    }
 
-        self.state = new_state;
+    // Get the cell under the mouse pointer, which may be off the board
+    fn pointer_cell(&self) -> (i32, i32) {
+        return self.camera.cell_at(olc::get_mouse_x(), olc::get_mouse_y());
     }
 
-    // Draw the game state to the screen
-    fn draw(&self) {
-        olc::clear(olc::BLACK);
-        for y in 0..self.state_height {
-            for x in 0..self.state_width {
-                if self.state[x][y] {
-                    olc::draw(x as i32, y as i32, olc::WHITE);
+    // Get the cell under the mouse pointer, if it's on the board
+    fn hovered_cell(&self) -> Option<(usize, usize)> {
+        let (x, y) = self.pointer_cell();
+        if (0..self.game.state_width as i32).contains(&x)
+                && (0..self.game.state_height as i32).contains(&y) {
+            return Some((x as usize, y as usize));
+        } else {
+            return None;
+        }
+    }
+
+    // Crop the board down to its live cells, save it, and center the camera on what's left
+    fn crop_and_save(&mut self) {
+        let bounds = match self.game.bounding_box() {
+            Some(bounds) => bounds,
+            None => {
+                eprintln!("WARNING: Nothing to crop, since the board is empty");
+                return;
+            },
+        };
+
+        self.game.crop(bounds);
+        if let Some(coupling) = &mut self.coupling {
+            coupling.partner.crop(bounds);
+        }
+        self.unframe();
+        self.camera.center(self.game.state_width, self.game.state_height,
+            olc::screen_width(), olc::screen_height());
+
+        let path = format!("rustlife_{}_cropped.rle", self.game.generation);
+        match self.game.save_rle(&path) {
+            Ok(()) => {
+                println!("Cropped to {}x{} and saved `{}`", bounds.width, bounds.height, path);
+            },
+            Err(e) => eprintln!("ERROR: Couldn't save `{}`: {}", path, e),
+        }
+    }
+
+    // Handle input to the save dialog, saving and closing it once a name is confirmed
+    fn update_save_dialog(&mut self) {
+        let action = match &mut self.save_dialog {
+            Some(dialog) => dialog.handle_input(),
+            None => return,
+        };
+        match action {
+            dialog::Action::Editing => {},
+            dialog::Action::Cancel => self.save_dialog = None,
+            dialog::Action::Save(path, format) => {
+                let result = match format {
+                    dialog::Format::Rle => self.game.save_rle(&path),
+                    dialog::Format::Svg => self.game.save_svg(&path, &self.svg_style),
+                };
+                match result {
+                    Ok(()) => {
+                        println!("Saved `{}`", path);
+                        self.save_dialog = None;
+                    },
+                    Err(e) => if let Some(dialog) = &mut self.save_dialog {
+                        dialog.message = Some(format!("Couldn't save: {}", e));
+                    },
                 }
-            }
+            },
         }
     }
 
-    // Get the number of living neighbors of the specified cell
-    fn cell_get_neighbors(&self, x: i32, y: i32) -> u8 {
-        let mut total = 0;
-        for yofs in -1..=1 {
-            for xofs in -1..=1 {
-                let x2 = (x + xofs) as usize;
-                let y2 = (y + yofs) as usize;
+    // Run and draw a frame of the rule explorer instead of the board. Clicking a thumbnail adopts
+    // its rule and goes back to the board, as do Y and ESCAPE without changing anything.
+    fn update_explorer(&mut self, elapsed_time: f32) -> Result<(), olc::Error> {
+        let (width, height) = (olc::screen_width(), olc::screen_height());
+        // Some once the explorer closes, holding the rule that was clicked if there was one
+        let closed_with = match &mut self.explorer {
+            Some(explorer) => {
+                // One generation at a time, at the simulation's rate, so they're easy to follow
+                self.update_counter += elapsed_time;
+                if self.update_counter >= self.update_delta {
+                    explorer.update();
+                    self.update_counter = (self.update_counter - self.update_delta)
+                        .min(self.update_delta);
+                }
 
-                if (0..self.state_width).contains(&x2)          // x bounds check
-                        && (0..self.state_height).contains(&y2) // y bounds check
-                        && (xofs != 0 || yofs != 0) // Don't count center cell
-                        && self.state[x2][y2] {
-                    total += 1;
+                let hovered = explorer.thumbnail_at(olc::get_mouse_x(), olc::get_mouse_y(),
+                    width, height);
+                explorer.draw(hovered)?;
+                if olc::get_key(KEY_EXPLORE).pressed || olc::get_key(KEY_UNFRAME).pressed {
+                    Some(None)
+                } else if olc::get_mouse(0).pressed && hovered.is_some() {
+                    Some(hovered.map(|index| explorer.games[index].rule))
+                } else {
+                    None
                 }
+            },
+            None => return Ok(()),
+        };
+
+        if let Some(rule) = closed_with {
+            self.explorer = None;
+            if let Some(rule) = rule {
+                self.game.rule = rule;
+                println!("Rule: {}", rule);
             }
         }
-        return total;
+        return Ok(());
     }
 
-    // Reset to an empty state
-    fn empty_state(&mut self) {
-        self.state = vec![vec![false; self.state_height]; self.state_width];
+    // Remember the current state so the next edit can be undone
+    fn snapshot_for_undo(&mut self) {
+        if self.undo_stack.len() == UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(self.game.state.clone());
     }
 
-    // Set each bit of the state randomly
-    fn randomize_state(&mut self) {
-        self.state = vec![vec![false; self.state_height]; self.state_width];
-        for y in 0..self.state_height {
-            for x in 0..self.state_width {
-                // Randomly set each cell to true or false
-                self.state[x][y] = rand::random();
-            }
+    // Go back to the state from before the last edit
+    fn undo(&mut self) {
+        let size = (self.game.state_width, self.game.state_height);
+        match self.undo_stack.pop() {
+            // Snapshots from before the board was cropped no longer fit
+            Some(state) if (state.len(), state[0].len()) == size => {
+                self.game.state = state;
+                self.game.mark_ever_alive();
+            },
+            Some(_) => self.undo_stack.clear(),
+            None => {},
         }
     }
-}
 
+    // Get the lines of the HUD. Changed is the number of cells that differ from the previous
+    // generation, followed by its running average.
+    fn hud_lines(&self) -> Vec<String> {
+        let population = self.game.population();
+        let changed = match (self.activity.last(), self.activity.average()) {
+            (Some(last), Some(average)) => format!("Changed {} (avg {:.1})", last, average),
+            _ => "Changed -".to_string(),
+        };
+        let mut lines = vec![
+            format!("Gen {}", self.game.generation),
+            format!("Pop {}", population),
+            changed,
+        ];
+        if let Some(limit) = self.max_generation {
+            lines.push(format!("Left {}", limit.saturating_sub(self.game.generation)));
+        }
+        return lines;
+    }
+
+    // Check whether the simulation is paused because it reached the generation limit
+    fn at_generation_limit(&self) -> bool {
+        return self.step && self.max_generation.is_some_and(|limit| self.game.generation >= limit);
+    }
 
-// Utility function to get a command line arg or return a default value
-fn parse_arg<T: std::str::FromStr>(arg_matches: &clap::ArgMatches, arg: &str, default: T) -> T {
-    if let Some(string) = &arg_matches.value_of(arg) {
-        if let Ok(n) = string.parse::<T>() {
-            return n;
+    // Lay out the minimap for the current board
+    fn minimap(&self) -> minimap::Minimap {
+        return minimap::Minimap::new(self.game.state_width, self.game.state_height,
+            olc::screen_width());
+    }
+
+    // Draw lines across the screen through the cell under the pointer, labelled with its
+    // coordinates. The label moves to the other side of the pointer near the screen's edges.
+    fn draw_crosshair(&self) -> Result<(), olc::Error> {
+        let (x, y) = self.pointer_cell();
+        let (screen_x, screen_y) = self.camera.screen_position(x, y);
+        let half = self.camera.zoom / 2;
+        let (screen_x, screen_y) = (screen_x + half, screen_y + half);
+        olc::draw_line(0, screen_y, olc::screen_width() - 1, screen_y, CROSSHAIR_COLOR);
+        olc::draw_line(screen_x, 0, screen_x, olc::screen_height() - 1, CROSSHAIR_COLOR);
+
+        let label = format!("({}, {})", x, y);
+        let label_width = label.len() as i32 * 8;
+        let label_x = if screen_x + 3 + label_width > olc::screen_width() {
+            screen_x - 2 - label_width
         } else {
-            eprintln!("ERROR: Couldn't parse value for argument `{}`", arg);
-            std::process::exit(1);
+            screen_x + 3
+        };
+        let label_y = if screen_y + 11 > olc::screen_height() {
+            screen_y - 10
+        } else {
+            screen_y + 3
+        };
+        return olc::draw_string(label_x, label_y, &label, CROSSHAIR_COLOR);
+    }
+
+    // Do what the active tool does with the mouse this frame
+    fn use_tool(&mut self) {
+        let mouse = olc::get_mouse(0);
+        match self.tool {
+            Tool::Toggle => if mouse.pressed {
+                if let Some((x, y)) = self.hovered_cell() {
+                    let alive = self.game.state[x][y];
+                    self.game.set_cell(x, y, !alive);
+                }
+            },
+            Tool::Paint | Tool::Erase => {
+                // The wheel resizes the brush
+                let wheel = olc::get_mouse_wheel().signum();
+                self.brush_size = (self.brush_size + wheel).clamp(1, MAX_BRUSH_SIZE);
+
+                // A whole stroke undoes at once
+                if mouse.pressed {
+                    self.snapshot_for_undo();
+                }
+                if mouse.held {
+                    let (x, y) = self.pointer_cell();
+                    self.game.paint(x, y, self.brush_size, self.tool == Tool::Paint);
+                }
+            },
+            Tool::Stamp(placing) => if mouse.pressed {
+                // Stamp a fresh copy of the pattern, which stays armed for the next click
+                let pattern = placing.pattern(&self.library);
+                let (x, y) = self.pointer_cell();
+                let (x, y) = placing.corner(&pattern, x, y);
+                self.snapshot_for_undo();
+                self.game.stamp_pattern(&pattern, x, y);
+            },
+            Tool::Inspect => if mouse.pressed {
+                if let Some((x, y)) = self.hovered_cell() {
+                    let state = if self.game.state[x][y] { "alive" } else { "dead" };
+                    let neighbors = self.game.cell_get_neighbors(x as i32, y as i32);
+                    println!("({}, {}) is {} with {} neighbors", x, y, state, neighbors);
+                }
+            },
+            Tool::Region(rule) => {
+                let pointer = self.pointer_cell();
+                if mouse.pressed && olc::get_key(olc::Key::SHIFT).held {
+                    // Take away the topmost region under the pointer
+                    let index = self.hovered_cell().and_then(|(x, y)| {
+                        self.game.regions.iter().rposition(|(bounds, _)| bounds.contains(x, y))
+                    });
+                    if let Some(index) = index {
+                        let (bounds, rule) = self.game.regions.remove(index);
+                        println!("Removed the {}x{} region at ({}, {}) with rule {}",
+                            bounds.width, bounds.height, bounds.x, bounds.y, rule);
+                    }
+                } else if mouse.pressed {
+                    self.region_start = Some(pointer);
+                } else if mouse.released {
+                    if let Some(bounds) = self.region_start.take()
+                            .map(|start| self.region_between(start, pointer)) {
+                        self.game.regions.push((bounds, rule));
+                        println!("Added a {}x{} region at ({}, {}) with rule {}", bounds.width,
+                            bounds.height, bounds.x, bounds.y, rule);
+                    }
+                }
+            },
         }
-    } else {
-        return default;
     }
-}
 
-fn main() {
-    // Handle command line args
-    let args = clap::App::new("RustLife")
-        .version("0.1.0")
-        .about("A Rust implementation of Conway's Game of Life")
-        .arg(clap::Arg::with_name("width")
-            .short("W")
-            .long("width")
-            .value_name("WIDTH")
-            .help("Sets the simulation space's width")
-            .takes_value(true))
-        .arg(clap::Arg::with_name("height")
-            .short("H")
-            .long("height")
-            .value_name("HEIGHT")
-            .help("Sets the simulation space's height")
-            .takes_value(true))
-        .arg(clap::Arg::with_name("scale")
-            .short("S")
-            .long("scale")
-            .value_name("SCALE")
-            .help("Sets the display scale, i.e. how many pixels each cell should take up on the \
-                screen")
-            .takes_value(true))
-        .arg(clap::Arg::with_name("start-paused")
-            .long("start-paused")
-            .help("Whether to start the simulation paused"))
-        .get_matches();
+    // Get the rectangle of cells with two corners at the given cells, cut down to the board
+    fn region_between(&self, a: (i32, i32), b: (i32, i32)) -> Rect {
+        let clamp_x = |x: i32| x.clamp(0, self.game.state_width as i32 - 1) as usize;
+        let clamp_y = |y: i32| y.clamp(0, self.game.state_height as i32 - 1) as usize;
+        let (left, right) = (clamp_x(a.0.min(b.0)), clamp_x(a.0.max(b.0)));
+        let (top, bottom) = (clamp_y(a.1.min(b.1)), clamp_y(a.1.max(b.1)));
+        return Rect { x: left, y: top, width: right - left + 1, height: bottom - top + 1 };
+    }
 
-    // Set screen parameters
-    let screen_width  = parse_arg(&args, "width",  SCREEN_WIDTH);
-    let screen_height = parse_arg(&args, "height", SCREEN_HEIGHT);
-    let screen_scale  = parse_arg(&args, "scale",  SCREEN_SCALE);
+    // Write the name of every component the dictionary recognizes just above it. Components it
+    // doesn't know are left alone.
+    fn draw_pattern_labels(&self, dictionary: &patterns::Dictionary) -> Result<(), olc::Error> {
+        let (labels, count) = components::label(&self.game.state);
+        let mut cells = vec![Vec::new(); count];
+        for x in 0..labels.len() {
+            for y in 0..labels[x].len() {
+                if labels[x][y] != 0 {
+                    cells[labels[x][y] as usize - 1].push((x as i32, y as i32));
+                }
+            }
+        }
+        for (cells, (bounds, _)) in cells.iter().zip(components::bounds(&labels, count)) {
+            if let Some(name) = dictionary.name(cells) {
+                let (x, y) = self.camera.screen_position(bounds.x as i32, bounds.y as i32);
+                olc::draw_string(x, y - 9, name, LABEL_COLOR)?;
+            }
+        }
+        return Ok(());
+    }
 
-    // Initialize the application
-    let mut game = GameOfLife::new(screen_width as usize, screen_height as usize);
-    game.randomize_state();
-    let mut application = Application::new(game);
+    // Frame the camera on the next or previous component of the board, relabelling it first so
+    // the selection follows any changes since the last one
+    fn frame_component(&mut self, forward: bool) {
+        let (labels, count) = components::label(&self.game.state);
+        if count == 0 {
+            self.unframe();
+            return;
+        }
+        let index = match &self.framing {
+            Some(framing) if forward => (framing.index + 1) % count,
+            Some(framing) => (framing.index.min(count - 1) + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        };
+        let camera = self.framing.as_ref().map_or(self.camera, |framing| framing.camera);
+        let (bounds, cells) = components::bounds(&labels, count)[index];
+        self.camera.fit(bounds, olc::screen_width(), olc::screen_height(), MAX_FRAME_ZOOM);
+        self.framing = Some(Framing {
+            index: index, count: count, bounds: bounds, cells: cells, camera: camera,
+        });
+    }
 
-    // Start in step mode if specified on the command line
-    if args.is_present("start-paused") {
-        application.step = true;
+    // Put the camera back the way it was before framing components
+    fn unframe(&mut self) {
+        if let Some(framing) = self.framing.take() {
+            self.camera = framing.camera;
+        }
     }
 
-    // Start the application
-    olc::start_with_full_screen_and_vsync(
-        "RustLife",
-        &mut application,
-        screen_width,
-        screen_height,
-        screen_scale,
-        screen_scale,
-        false,
-        true
-    ).unwrap();
+    // Draw a bar labelled with how many cells it spans in a bottom corner, the right one unless
+    // the HUD is there. Returns the part of the screen it covers.
+    fn draw_scale_bar(&self) -> Result<Rect, olc::Error> {
+        let (cells, length) = self.camera.scale_bar(SCALE_BAR_MAX_LENGTH);
+        let label = if cells == 1 { "1 cell".to_string() } else { format!("{} cells", cells) };
+        let width = length.max(label.len() as i32 * TEXT_CHAR_WIDTH) + 4;
+        let height = TEXT_LINE_HEIGHT + 6;
+        let (left, bottom) = match self.hud_corner {
+            Corner::BottomRight if self.show_legend => (1, olc::screen_height() - LEGEND_HEIGHT),
+            Corner::BottomRight => (1, olc::screen_height()),
+            _ => (olc::screen_width() - width - 1, olc::screen_height()),
+        };
+        let top = bottom - height - 1;
+
+        olc::fill_rect(left, top, width, height, SCALE_BAR_BACKGROUND);
+        olc::draw_string(left + 2, top + 2, &label, olc::WHITE)?;
+        let (start, end, y) = (left + 2, left + 2 + length - 1, top + height - 4);
+        olc::draw_line(start, y, end, y, olc::WHITE);
+        olc::draw_line(start, y - 2, start, y + 2, olc::WHITE);
+        olc::draw_line(end, y - 2, end, y + 2, olc::WHITE);
+        return Ok(Rect { x: left.max(0) as usize, y: top.max(0) as usize, width: width as usize,
+            height: height as usize });
+    }
+
+    // Save the view as an image at the window's resolution. The cells are drawn again rather
+    // than copied from the screen, which leaves out the HUD and other overlays, but the scale
+    // bar, if there is one, is copied from where it was just drawn.
+    fn save_view(&self, scale_bar: Option<Rect>) {
+        let (width, height) = (olc::screen_width() as usize, olc::screen_height() as usize);
+        let mut pixels = png::render_view(&self.game.state, &self.camera, width, height);
+        if let Some(bar) = scale_bar {
+            let screen = olc::layer::get_primary_draw_target();
+            for y in bar.y..(bar.y + bar.height).min(height) {
+                for x in bar.x..(bar.x + bar.width).min(width) {
+                    let pixel = screen.get_pixel(x as i32, y as i32);
+                    let index = (y * width + x) * 3;
+                    pixels[index..index + 3].copy_from_slice(&[pixel.r, pixel.g, pixel.b]);
+                }
+            }
+        }
+        save_png(&format!("rustlife_{}_view.png", self.game.generation), width, height, &pixels);
+    }
+
+    // Draw lines of text stacked in the HUD's corner, top line first, keeping clear of the minimap
+    // and rule legend where they share a corner
+    fn draw_text_lines(&self, lines: &[(String, olc::Pixel)], minimap: &minimap::Minimap)
+            -> Result<(), olc::Error> {
+        let (screen_width, screen_height) = (olc::screen_width(), olc::screen_height());
+        let block_height = lines.len() as i32 * TEXT_LINE_HEIGHT;
+        let mut y = match self.hud_corner {
+            Corner::TopLeft => 1,
+            Corner::TopRight if self.show_minimap => minimap.top + minimap.height + 2,
+            Corner::TopRight => 1,
+            Corner::BottomLeft if self.show_legend => screen_height - LEGEND_HEIGHT - block_height,
+            Corner::BottomLeft | Corner::BottomRight => screen_height - block_height,
+        };
+        for (text, color) in lines {
+            let x = match self.hud_corner {
+                Corner::TopLeft | Corner::BottomLeft => 1,
+                Corner::TopRight | Corner::BottomRight => {
+                    screen_width - text.len() as i32 * TEXT_CHAR_WIDTH - 1
+                },
+            };
+            olc::draw_string(x, y, text, *color)?;
+            y += TEXT_LINE_HEIGHT;
+        }
+        return Ok(());
+    }
+
+    // Draw the rule as two rows of boxes indexed by neighbor count. The top row shows which
+    // counts bring a dead cell to life, and the bottom row which ones keep a live cell alive.
+    fn draw_rule_legend(&self) -> Result<(), olc::Error> {
+        let rule = &self.game.rule;
+        let box_size = LEGEND_BOX_SIZE;
+        let left = 1;
+        let top = olc::screen_height() - LEGEND_HEIGHT;
+
+        olc::draw_string(left, top, &rule.to_string(), olc::WHITE)?;
+        let rows = [("B", &rule.birth), ("S", &rule.survival)];
+        for (row, &(label, counts)) in rows.iter().enumerate() {
+            let y = top + 10 + row as i32 * box_size;
+            olc::draw_string(left, y + 1, label, olc::WHITE)?;
+            for n in 0..9 {
+                let x = left + 9 + n as i32 * box_size;
+                let color = if counts[n] { LEGEND_ON_COLOR } else { LEGEND_OFF_COLOR };
+                olc::fill_rect(x, y, box_size - 1, box_size - 1, color);
+                olc::draw_string(x, y + 1, &n.to_string(), olc::WHITE)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+impl olc::Application for Application {
+    // Called on application creation and destruction respectively
+    fn on_user_create(&mut self) -> Result<(), olc::Error> {
+        // The window can be smaller than the board, so start looking at the middle of it
+        self.camera.center(self.game.state_width, self.game.state_height, olc::screen_width(),
+            olc::screen_height());
+        // The window's open by now, so it can be moved and have its borders taken away
+        if let Err(e) = window::place(self.placement) {
+            eprintln!("WARNING: Couldn't place the window: {}", e);
+        }
+        return Ok(());
+    }
+    fn on_user_destroy(&mut self) -> Result<(), olc::Error> {
+        if let Some(log) = &mut self.population_log {
+            if let Err(e) = log.flush() {
+                eprintln!("ERROR: Couldn't finish writing the population log: {}", e);
+            }
+        }
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.flush() {
+                eprintln!("ERROR: Couldn't finish writing the recording: {}", e);
+            }
+        }
+        return Ok(());
+    }
+
+    // Called every frame
+    fn on_user_update(&mut self, elapsed_time: f32) -> Result<(), olc::Error> {
+        if self.explorer.is_some() {
+            let result = self.update_explorer(elapsed_time);
+            self.limit_frame_rate();
+            return result;
+        }
+
+        // Handle frame advance. Holding the jog key runs the simulation whatever the step mode,
+        // and pauses it again on release.
+        let jog = olc::get_key(KEY_JOG);
+        if jog.released {
+            self.step = true;
+        }
+        if self.diff_against.is_some() || self.save_dialog.is_some() {
+            // Comparing two patterns or naming a save, which stepping would only muddle
+            self.rate_meter.clear();
+        } else if self.step && !jog.held {
+            // Advance frame on keypress
+            self.rate_meter.clear();
+            if olc::get_key(KEY_STEP).pressed {
+                self.update_game();
+            }
+        } else {
+            // Run as many generations as the requested rate calls for, catching up when frames
+            // take longer than a generation. Once the frame's time budget is spent, what's left
+            // carries over to the next frame so input and drawing still get their turn.
+            self.update_counter += elapsed_time;
+            let started = std::time::Instant::now();
+            let mut updates = 0;
+            while self.update_counter >= self.update_delta && updates < MAX_UPDATES_PER_FRAME {
+                self.update_game();
+                self.update_counter -= self.update_delta;
+                updates += 1;
+                if self.step && !jog.held {
+                    break;  // Something paused it, like reaching the generation limit
+                }
+                if started.elapsed() >= self.tick_budget {
+                    break;
+                }
+            }
+
+            // Too far behind to ever catch up, so stop trying rather than bursting later
+            let backlog = self.update_delta * MAX_UPDATES_PER_FRAME as f32;
+            if updates == MAX_UPDATES_PER_FRAME || self.update_counter > backlog {
+                self.update_counter = self.update_counter.min(self.update_delta);
+            }
+            self.rate_meter.tick(elapsed_time, updates);
+        }
+
+        // Input handling
+        if self.save_dialog.is_some() {
+            // The dialog takes every key while it's open
+            self.update_save_dialog();
+        } else if olc::get_key(KEY_EMPTY).pressed {
+            // Reset with empty state
+            self.game.empty_state();
+            self.activity.clear();
+            if let Some(notifier) = &mut self.notifier {
+                notifier.clear();
+            }
+            if let Some(coupling) = &mut self.coupling {
+                coupling.partner.empty_state();
+            }
+            self.step = true;
+        } else if olc::get_key(KEY_RESET).pressed {
+            // Reset with random state
+            self.game.randomize_state();
+            self.activity.clear();
+            if let Some(notifier) = &mut self.notifier {
+                notifier.clear();
+            }
+            if let Some(coupling) = &mut self.coupling {
+                coupling.partner.randomize_state();
+            }
+        } else if olc::get_key(KEY_STEP_BACK).pressed && self.game.previous.is_some() {
+            // Pause and run the reversible rule backward a generation
+            self.step = true;
+            self.game.step_back();
+            if let Some(comparison) = &mut self.comparison {
+                comparison.step_back();
+            }
+        } else if olc::get_key(KEY_STEP_TOGGLE).pressed {
+            // Toggle step mode
+            self.step = !self.step;
+            self.update_counter = 0.0;
+        } else if olc::get_key(KEY_INSPECT).pressed {
+            // Toggle the hovered cell readout
+            self.show_inspector = !self.show_inspector;
+        } else if olc::get_key(KEY_LEGEND).pressed {
+            // Toggle the rule legend
+            self.show_legend = !self.show_legend;
+        } else if olc::get_key(KEY_NEXT_RULE).pressed {
+            // Switch to the next well-known rule, or the one new regions get if drawing them
+            if let Tool::Region(rule) = &mut self.tool {
+                *rule = rule.next_named().1;
+            } else {
+                let (name, rule) = self.game.rule.next_named();
+                self.game.rule = rule;
+                println!("Rule: {} ({})", name, rule);
+            }
+        } else if olc::get_key(KEY_EXPLORE).pressed {
+            // Compare the rule with its neighbors, all starting from the same soup
+            self.explorer = Some(explorer::Explorer::new(self.game.rule, self.game.density,
+                self.game.rng.gen()));
+            self.update_counter = 0.0;
+        } else if olc::get_key(KEY_UNTOUCHED).pressed {
+            // Toggle tinting cells that have never been alive
+            self.draw_options.show_untouched = !self.draw_options.show_untouched;
+        } else if olc::get_key(KEY_TRAILS).pressed {
+            // Toggle fading trails behind cells that die
+            self.effects.trails = !self.effects.trails;
+        } else if olc::get_key(KEY_FLASH).pressed {
+            // Toggle flashing cells that are born
+            self.effects.flash = !self.effects.flash;
+        } else if olc::get_key(KEY_MINIMAP).pressed {
+            // Toggle the overview of the whole board
+            self.show_minimap = !self.show_minimap;
+        } else if olc::get_key(KEY_CROSSHAIR).pressed {
+            // Toggle lines through the pointer for lining things up
+            self.show_crosshair = !self.show_crosshair;
+        } else if olc::get_key(KEY_COMPONENTS).pressed {
+            // Toggle coloring each cluster of touching cells differently
+            self.components = match self.components {
+                Some(_) => None,
+                None => Some(components::Tracker::new()),
+            };
+        } else if olc::get_key(KEY_HEAT).pressed {
+            // Toggle coloring the background by neighbor count
+            self.draw_options.show_heat = !self.draw_options.show_heat;
+        } else if olc::get_key(KEY_IDENTIFY).pressed {
+            // Toggle labelling the patterns the dictionary knows
+            self.dictionary = match self.dictionary {
+                Some(_) => None,
+                None => Some(patterns::Dictionary::known()),
+            };
+        } else if let Some(&(_, dx, dy)) = [(KEY_NUDGE_LEFT, -1, 0), (KEY_NUDGE_RIGHT, 1, 0),
+                (KEY_NUDGE_UP, 0, -1), (KEY_NUDGE_DOWN, 0, 1)].iter()
+                .find(|&&(key, _, _)| olc::get_key(key).pressed) {
+            if olc::get_key(KEY_SCROLL_ARENA).held {
+                // Scroll the arena across the board, leaving the cells it passes frozen
+                let step = if olc::get_key(olc::Key::SHIFT).held {
+                    ARENA_SCROLL_FAST_STEP
+                } else {
+                    ARENA_SCROLL_STEP
+                };
+                match self.game.scroll_arena(dx * step, dy * step) {
+                    Some(arena) => self.follow_arena(arena),
+                    None => eprintln!("WARNING: There's no arena to scroll. Set one with --arena"),
+                }
+            } else {
+                // Move every live cell one step, for lining patterns up exactly
+                self.snapshot_for_undo();
+                self.game.shift(dx, dy);
+            }
+        } else if olc::get_key(KEY_UNDO).pressed {
+            // Undo the last edit
+            self.undo();
+        } else if olc::get_key(KEY_HUD).pressed {
+            // Toggle the generation, population and activity readout
+            self.show_hud = !self.show_hud;
+        } else if olc::get_key(KEY_HUD_CORNER).pressed {
+            // Move the text readouts out of the way of whatever's being watched
+            self.hud_corner = self.hud_corner.next();
+        } else if olc::get_key(KEY_ENTROPY).pressed {
+            // Toggle the entropy readout, starting with an estimate of the board as it is
+            self.show_entropy = !self.show_entropy;
+            self.entropy = Some(stats::block_entropy(&self.game.state));
+        } else if olc::get_key(KEY_CLEAR_LIMIT).pressed && self.max_generation.is_some() {
+            // Drop the generation limit and carry on past it
+            if self.at_generation_limit() {
+                self.step = false;
+                self.update_counter = 0.0;
+            }
+            self.max_generation = None;
+        } else if olc::get_key(KEY_NEXT_COMPONENT).pressed {
+            // Frame the next cluster of cells, or the previous one with shift
+            self.frame_component(!olc::get_key(olc::Key::SHIFT).held);
+        } else if olc::get_key(KEY_TOOL_TOGGLE).pressed {
+            self.tool = Tool::Toggle;
+        } else if olc::get_key(KEY_TOOL_PAINT).pressed {
+            self.tool = Tool::Paint;
+        } else if olc::get_key(KEY_TOOL_ERASE).pressed {
+            self.tool = Tool::Erase;
+        } else if olc::get_key(KEY_TOOL_STAMP).pressed {
+            // Keep the pattern and rotation if already stamping
+            if !matches!(self.tool, Tool::Stamp(_)) {
+                self.tool = Tool::Stamp(Placement { index: 0, quarter_turns: 0 });
+            }
+        } else if olc::get_key(KEY_TOOL_INSPECT).pressed {
+            self.tool = Tool::Inspect;
+        } else if olc::get_key(KEY_TOOL_REGION).pressed {
+            // New regions start out with a different rule from the rest of the board
+            if !matches!(self.tool, Tool::Region(_)) {
+                self.tool = Tool::Region(self.game.rule.next_named().1);
+            }
+        } else if olc::get_key(KEY_PLACE).pressed {
+            // Arm the next library pattern for stamping, or go back to toggling after the last
+            self.tool = match self.tool {
+                Tool::Stamp(placing) if placing.index + 1 < self.library.len() => {
+                    Tool::Stamp(Placement { index: placing.index + 1, ..placing })
+                },
+                Tool::Stamp(_) => Tool::Toggle,
+                _ => Tool::Stamp(Placement { index: 0, quarter_turns: 0 }),
+            };
+        } else if olc::get_key(KEY_ROTATE).pressed {
+            // Turn the pattern being stamped
+            if let Tool::Stamp(placing) = &mut self.tool {
+                placing.quarter_turns = (placing.quarter_turns + 1) % 4;
+            }
+        } else if olc::get_key(KEY_UNFRAME).pressed && self.tool != Tool::Toggle {
+            self.tool = Tool::Toggle;
+        } else if olc::get_key(KEY_UNFRAME).pressed {
+            self.unframe();
+        } else if olc::get_key(KEY_CROP).pressed {
+            // Shrink the board to the pattern and save it
+            self.crop_and_save();
+        } else if olc::get_key(KEY_GLIDER).pressed {
+            // Stamp a glider at the cursor heading away from the center of the board, or toward
+            // it with shift held
+            let (x, y) = self.pointer_cell();
+            let mut dx = if x * 2 >= self.game.state_width as i32 { 1 } else { -1 };
+            let mut dy = if y * 2 >= self.game.state_height as i32 { 1 } else { -1 };
+            if olc::get_key(olc::Key::SHIFT).held {
+                dx = -dx;
+                dy = -dy;
+            }
+            let glider = patterns::glider_heading(dx, dy);
+            self.snapshot_for_undo();
+            self.game.stamp_pattern(&glider, x - glider.width() / 2, y - glider.height() / 2);
+        } else if olc::get_key(KEY_SAVE_AS).pressed {
+            // Open the save dialog, with the name the other saves would use filled in
+            let name = format!("rustlife_{}", self.game.generation);
+            self.save_dialog = Some(dialog::SaveDialog::new(&name));
+        } else if olc::get_key(KEY_CHECKPOINT).pressed {
+            // Save the board along with the random number generator's position
+            self.base = Some(checkpoint::Base::capture(&self.game));
+            match std::fs::write(CHECKPOINT_PATH, checkpoint::encode(&self.game)) {
+                Ok(()) => println!("Saved `{}`", CHECKPOINT_PATH),
+                Err(e) => eprintln!("ERROR: Couldn't save `{}`: {}", CHECKPOINT_PATH, e),
+            }
+        } else if olc::get_key(KEY_RESTORE).pressed {
+            // Go back to the last checkpoint, to replay the same random choices from there
+            self.snapshot_for_undo();
+            let restored = std::fs::File::open(CHECKPOINT_PATH).and_then(|file| {
+                checkpoint::restore(&mut self.game, std::io::BufReader::new(file))
+            });
+            match restored {
+                Ok(_) => {
+                    self.base = Some(checkpoint::Base::capture(&self.game));
+                    self.activity.clear();
+                    if let Some(notifier) = &mut self.notifier {
+                        notifier.clear();
+                    }
+                    println!("Restored `{}` at generation {}", CHECKPOINT_PATH,
+                        self.game.generation);
+                },
+                Err(e) => {
+                    self.undo_stack.pop();
+                    eprintln!("ERROR: Couldn't restore `{}`: {}", CHECKPOINT_PATH, e);
+                },
+            }
+        } else if olc::get_key(KEY_SOFT_RESET).pressed {
+            // Put back just the cells that changed since the checkpoint, keeping it around to
+            // come back to after the next experiment
+            self.snapshot_for_undo();
+            let reverted = match &self.base {
+                Some(base) => base.revert(&mut self.game),
+                None => Err("There's no checkpoint to go back to yet. Press F5 to set one."
+                    .to_string()),
+            };
+            match reverted {
+                Ok(changed) => {
+                    self.activity.clear();
+                    if let Some(notifier) = &mut self.notifier {
+                        notifier.clear();
+                    }
+                    println!("Reverted {} cells to the checkpoint at generation {}", changed,
+                        self.game.generation);
+                },
+                Err(e) => {
+                    self.undo_stack.pop();
+                    eprintln!("WARNING: {}", e);
+                },
+            }
+        } else if olc::get_key(KEY_SAVE_SVG).pressed {
+            // Save the pattern as a vector image named after the current generation
+            let path = format!("rustlife_{}.svg", self.game.generation);
+            match self.game.save_svg(&path, &self.svg_style) {
+                Ok(()) => println!("Saved `{}`", path),
+                Err(e) => eprintln!("ERROR: Couldn't save `{}`: {}", path, e),
+            }
+        } else if olc::get_key(KEY_SAVE_PNG).pressed {
+            // Save the whole board as an image with SHIFT held, or else what's on screen, once
+            // the scale bar is drawn
+            if olc::get_key(olc::Key::SHIFT).held {
+                let (width, height, pixels) = png::render(&self.game.state, 1);
+                save_png(&format!("rustlife_{}.png", self.game.generation), width, height,
+                    &pixels);
+            } else {
+                self.capture_view = true;
+            }
+        } else if olc::get_key(KEY_SCALE_BAR).pressed {
+            // Toggle the scale bar
+            self.show_scale_bar = !self.show_scale_bar;
+        } else if olc::get_key(KEY_BIRTH_COLORS).pressed {
+            // Toggle coloring cells by the generation they were born in
+            let enabled = self.game.birth_gen.is_none();
+            self.game.track_births(enabled);
+        } else if olc::get_key(KEY_COMPARE).pressed {
+            // Copy the board into a pane of its own, with the view centered in whichever part of
+            // the screen is left for the board
+            match self.toggle_comparison() {
+                Ok(()) => {
+                    let width = self.comparison_left().unwrap_or_else(olc::screen_width);
+                    self.camera.center(self.game.state_width, self.game.state_height, width,
+                        olc::screen_height());
+                    if self.comparison.is_some() {
+                        println!("Copied the board at generation {} into a comparison pane",
+                            self.game.generation);
+                    }
+                },
+                Err(e) => eprintln!("WARNING: {}", e),
+            }
+        }
+
+        // Click the minimap to look at that part of the board, or anywhere else to use the tool
+        let minimap = self.minimap();
+        let (mouse_x, mouse_y) = (olc::get_mouse_x(), olc::get_mouse_y());
+        let in_comparison = self.comparison_left().is_some_and(|left| mouse_x >= left);
+        if self.show_minimap && minimap.contains(mouse_x, mouse_y) {
+            if olc::get_mouse(0).pressed {
+                let (x, y) = minimap.cell_at(mouse_x, mouse_y);
+                let width = self.comparison_left().unwrap_or_else(olc::screen_width);
+                self.camera.center_on(x, y, width, olc::screen_height());
+            }
+        } else if self.save_dialog.is_none() && !in_comparison {
+            // The copy is only there to watch, so tools only work on the board
+            self.use_tool();
+        }
+
+        // Effects run on elapsed time rather than generations, so they settle while paused
+        self.effects.observe(&self.game.state);
+        self.effects.advance(elapsed_time);
+
+        match &self.diff_against {
+            Some(other) => self.game.draw_diff(other, &self.camera),
+            None => self.game.draw(&self.draw_options, &self.camera),
+        }
+        if (self.game.state_width as i32) * self.camera.zoom < olc::screen_width()
+                || (self.game.state_height as i32) * self.camera.zoom < olc::screen_height() {
+            // Outline boards that don't fill the screen, like cropped ones
+            self.camera.draw_outline(0, 0, self.game.state_width, self.game.state_height,
+                olc::DARK_GREY);
+        }
+        if let Some(tracker) = &mut self.components {
+            tracker.update(&self.game.state);
+            tracker.draw(&self.camera);
+        }
+        if matches!(self.tool, Tool::Paint | Tool::Erase) && self.brush_size > 1 {
+            // Show what the brush covers
+            let (x, y) = self.pointer_cell();
+            let (left, top) = self.camera.screen_position(x - self.brush_size / 2,
+                y - self.brush_size / 2);
+            let size = self.brush_size * self.camera.zoom + 1;
+            olc::draw_rect(left - 1, top - 1, size, size, PLACE_PREVIEW_COLOR);
+        }
+        if let Tool::Stamp(placing) = self.tool {
+            // Show where the next click puts the pattern
+            let pattern = placing.pattern(&self.library);
+            let (x, y) = self.pointer_cell();
+            let (x, y) = placing.corner(&pattern, x, y);
+            let (left, top) = self.camera.screen_position(x, y);
+            olc::draw_rect(left - 1, top - 1, pattern.width() * self.camera.zoom + 1,
+                pattern.height() * self.camera.zoom + 1, PLACE_PREVIEW_COLOR);
+        }
+        if let Some(framing) = &self.framing {
+            let bounds = framing.bounds;
+            self.camera.draw_outline(bounds.x, bounds.y, bounds.width, bounds.height, FRAME_COLOR);
+        }
+        if let Some(arena) = self.game.arena {
+            self.camera.draw_outline(arena.x, arena.y, arena.width, arena.height, ARENA_COLOR);
+        }
+        for (bounds, rule) in &self.game.regions {
+            self.camera.draw_outline(bounds.x, bounds.y, bounds.width, bounds.height, REGION_COLOR);
+            let (x, y) = self.camera.screen_position(bounds.x as i32, bounds.y as i32);
+            olc::draw_string(x, y - 10, &rule.to_string(), REGION_COLOR)?;
+        }
+        if let (Tool::Region(_), Some(start)) = (self.tool, self.region_start) {
+            // Show the region being dragged out
+            let bounds = self.region_between(start, self.pointer_cell());
+            self.camera.draw_outline(bounds.x, bounds.y, bounds.width, bounds.height,
+                PLACE_PREVIEW_COLOR);
+        }
+        if let Some(hint) = &mut self.goe_hint {
+            // Follow rule changes
+            if hint.rule != self.game.rule {
+                *hint = eden::Hint::new(self.game.rule);
+            }
+            hint.draw(&self.game.state, &self.camera);
+        }
+        self.draw_partner();
+        self.effects.draw(&self.camera);
+        if self.bevel && self.camera.zoom >= MIN_BEVEL_ZOOM {
+            for (x, y) in self.game.live_cells() {
+                self.camera.draw_bevel(x as usize, y as usize);
+            }
+        }
+
+        if let Some(dictionary) = &self.dictionary {
+            self.draw_pattern_labels(dictionary)?;
+        }
+        self.draw_comparison()?;
+        if self.show_crosshair {
+            self.draw_crosshair()?;
+        }
+
+        let scale_bar = if self.show_scale_bar { Some(self.draw_scale_bar()?) } else { None };
+        if self.capture_view {
+            self.capture_view = false;
+            self.save_view(scale_bar);
+        }
+
+        // Text readouts stack up in whichever corner the HUD is pinned to
+        let mut lines = Vec::new();
+        if self.show_hud {
+            lines.extend(self.hud_lines().into_iter().map(|line| (line, olc::WHITE)));
+
+            // Compare the rate the simulation is really running at with the one asked for
+            let requested = 1.0 / self.update_delta;
+            if let Some(actual) = self.rate_meter.rate {
+                let color = if actual < requested * RATE_SHORTFALL { olc::RED } else { olc::WHITE };
+                lines.push((format!("Rate {:.1}/{:.0} gen/s", actual, requested), color));
+            }
+        }
+
+        if let Some(entropy) = self.entropy.filter(|_| self.show_entropy) {
+            lines.push((format!("Entropy {:.3} bits", entropy), olc::WHITE));
+        }
+        if self.show_hud || self.tool != Tool::Toggle {
+            let mut text = format!("Tool: {}", self.tool.name(&self.library));
+            if matches!(self.tool, Tool::Paint | Tool::Erase) {
+                text += &format!(" {}x{}", self.brush_size, self.brush_size);
+            }
+            lines.push((text, TOOL_COLOR));
+        }
+        if let Some(framing) = &self.framing {
+            let text = format!("Component {}/{}: {}x{}, {} cells", framing.index + 1,
+                framing.count, framing.bounds.width, framing.bounds.height, framing.cells);
+            lines.push((text, FRAME_COLOR));
+        }
+        if self.at_generation_limit() {
+            lines.push(("Generation limit reached. Q to continue".to_string(), olc::YELLOW));
+        }
+
+        // Show the hovered cell's neighbor count as the active topology sees it
+        if self.show_inspector {
+            if let Some((x, y)) = self.hovered_cell() {
+                let neighbors = self.game.cell_get_neighbors(x as i32, y as i32);
+                let mut text = format!("({}, {}) {} neighbors", x, y, neighbors);
+                if let Some(decay) = &self.game.decay {
+                    text += &format!(", age {}", decay.age(x, y));
+                }
+                lines.push((text, olc::YELLOW));
+            }
+        }
+        self.draw_text_lines(&lines, &minimap)?;
+
+        if self.show_legend {
+            self.draw_rule_legend()?;
+        }
+
+        if self.show_minimap {
+            minimap.draw(&self.game.state, &self.camera);
+        }
+
+        if let Some(dialog) = &self.save_dialog {
+            dialog.draw()?;
+        }
+
+        // Keep the crash handle current. Skip the frame rather than wait if it's busy.
+        if let Some(handle) = &self.crash_handle {
+            if let Ok(mut snapshot) = handle.try_lock() {
+                match *snapshot {
+                    Some(ref mut game) => game.clone_from(&self.game),
+                    None => *snapshot = Some(self.game.clone()),
+                }
+            }
+        }
+        self.limit_frame_rate();
+        return Ok(());
+    }
+}
+
+// A corner of the screen that text can be pinned to
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Corner {
+    TopLeft,
+    TopRight,
+    BottomRight,
+    BottomLeft,
+}
+
+impl Corner {
+    // Get the next corner clockwise
+    fn next(self) -> Self {
+        return match self {
+            Corner::TopLeft     => Corner::TopRight,
+            Corner::TopRight    => Corner::BottomRight,
+            Corner::BottomRight => Corner::BottomLeft,
+            Corner::BottomLeft  => Corner::TopLeft,
+        };
+    }
+}
+
+impl std::str::FromStr for Corner {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "top-left"     => Ok(Corner::TopLeft),
+            "top-right"    => Ok(Corner::TopRight),
+            "bottom-right" => Ok(Corner::BottomRight),
+            "bottom-left"  => Ok(Corner::BottomLeft),
+            _ => Err(format!("Unknown corner `{}`", s)),
+        };
+    }
+}
+
+// Save RGB pixels as a PNG, reporting how it went
+fn save_png(path: &str, width: usize, height: usize, pixels: &[u8]) {
+    match std::fs::write(path, png::encode_rgb(width, height, pixels)) {
+        Ok(()) => println!("Saved `{}`", path),
+        Err(e) => eprintln!("ERROR: Couldn't save `{}`: {}", path, e),
+    }
+}
+
+// The order in which cells are updated each generation
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum UpdateMode {
+    Synchronous,  // Every cell reads the previous generation (standard Life)
+    RowMajor,     // Cells update in place, left to right and top to bottom
+    Random,       // Cells update in place, in an order shuffled every generation
+}
+
+impl std::str::FromStr for UpdateMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "sync" | "synchronous" => Ok(UpdateMode::Synchronous),
+            "row-major"            => Ok(UpdateMode::RowMajor),
+            "random"               => Ok(UpdateMode::Random),
+            _ => Err(format!("Unknown update order `{}`", s)),
+        };
+    }
+}
+
+// How the edges of the board behave when counting neighbors
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Topology {
+    Bounded,   // Cells past the edges are always dead
+    Torus,     // Both axes wrap around to the opposite edge
+    Cylinder,  // Only the horizontal axis wraps; the top and bottom edges are bounded
+    Reflect,   // Cells past an edge mirror the cells along it, so edge cells neighbor themselves
+}
+
+impl std::str::FromStr for Topology {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "bounded"  => Ok(Topology::Bounded),
+            "torus"    => Ok(Topology::Torus),
+            "cylinder" => Ok(Topology::Cylinder),
+            "reflect"  => Ok(Topology::Reflect),
+            _ => Err(format!("Unknown topology `{}`", s)),
+        };
+    }
+}
+
+// Optional overlays for `GameOfLife::draw`
+#[derive(Clone, Debug, Default)]
+struct DrawOptions {
+    show_untouched: bool,  // Tint cells that have never been alive
+    show_heat:      bool,  // Color dead cells by their live neighbor count
+}
+
+// A rectangle of cells
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Rect {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Rect {
+    // Check whether a cell is inside the rectangle
+    fn contains(&self, x: usize, y: usize) -> bool {
+        return (self.x..self.x + self.width).contains(&x)
+            && (self.y..self.y + self.height).contains(&y);
+    }
+}
+
+impl std::str::FromStr for Rect {
+    type Err = String;
+
+    // Parse a rectangle written as `x,y,width,height`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let numbers = s.split(',')
+            .map(|n| n.trim().parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| format!("Invalid rectangle `{}`", s))?;
+        match numbers[..] {
+            [x, y, width, height] if width > 0 && height > 0 => {
+                return Ok(Rect { x: x, y: y, width: width, height: height });
+            },
+            _ => return Err(format!("Expected a rectangle as `x,y,width,height`, got `{}`", s)),
+        }
+    }
+}
+
+/* ################################################
+# Conway's Game of Life                           #
+# Handles updating and drawing of the game state. #
+################################################ */
+#[derive(Clone)]
+struct GameOfLife {
+    state: Vec<Vec<bool>>,
+    ever_alive: Vec<Vec<bool>>,  // Cells that have been alive at any point since the last reset
+    state_width: usize,
+    state_height: usize,
+    generation: u64,  // Number of updates since the last reset
+    rule: Rule,
+    density: f64,  // Chance of each cell being alive after `randomize_state`
+    update_mode: UpdateMode,
+    topology: Topology,
+    arena: Option<Rect>,  // Only cells inside this update, and its edges act as the board's edges
+    rng: ChaCha12Rng,  // Kept at a known position so checkpoints can restore it
+    previous: Option<Vec<Vec<bool>>>,  // Generation before `state`, in second-order mode
+    regions: Vec<(Rect, Rule)>,  // Parts of the board with their own rule, later ones on top
+    decay: Option<decay::Decay>,  // Kills old cells at random on top of the rule, in --decay-prob
+    birth_gen: Option<Vec<Vec<u64>>>,  // Generation each live cell was born in, for --birth-colors
+}
+
+impl GameOfLife {
+    // Create a new game structure with a given width and height. The seed determines the sequence
+    // of random states, so two games created with the same seed randomize identically.
+    fn new(width: usize, height: usize, seed: u64) -> Self {
+        return GameOfLife {
+            state: vec![vec![false; height]; width],
+            ever_alive: vec![vec![false; height]; width],
+            state_width: width,
+            state_height: height,
+            generation: 0,
+            rule: Rule::life(),
+            density: DENSITY,
+            update_mode: UpdateMode::Synchronous,
+            topology: Topology::Bounded,
+            arena: None,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+            previous: None,
+            regions: Vec::new(),
+            decay: None,
+            birth_gen: None,
+        };
+    }
+
+    // Update the game state
+    fn update(&mut self) {
+        match self.update_mode {
+            _ if self.previous.is_some() => self.update_second_order(),
+            UpdateMode::Synchronous => self.update_synchronous(),
+            UpdateMode::RowMajor => {
+                let region = self.region();
+                for y in region.y..region.y + region.height {
+                    for x in region.x..region.x + region.width {
+                        self.update_cell_in_place(x, y);
+                    }
+                }
+            },
+            UpdateMode::Random => {
+                let region = self.region();
+                let mut order: Vec<(usize, usize)> = (region.y..region.y + region.height)
+                    .flat_map(|y| (region.x..region.x + region.width).map(move |x| (x, y)))
+                    .collect();
+                order.shuffle(&mut self.rng);
+                for (x, y) in order {
+                    self.update_cell_in_place(x, y);
+                }
+            },
+        }
+        let region = self.region();
+        if let Some(decay) = &mut self.decay {
+            decay.apply(&mut self.state, region, &mut self.rng);
+        }
+        self.generation += 1;
+        self.mark_ever_alive();
+        self.mark_births();
+        self.check_invariants();
+    }
+
+    // Check, in debug builds only, what every backend has to leave true after a generation:
+    // - `state` and `ever_alive` are both `state_width` columns of `state_height` cells, so
+    //   nothing indexed as `[x][y]` can go out of bounds or see a ragged column
+    // - every live cell is marked in `ever_alive`
+    // - the arena, if any, fits on the board, so the region loops never leave it
+    // - so do the rule regions
+    // There's no running population to compare against a recount, since `population` always
+    // recounts.
+    fn check_invariants(&self) {
+        let well_formed = |grid: &Vec<Vec<bool>>| {
+            grid.len() == self.state_width
+                && grid.iter().all(|column| column.len() == self.state_height)
+        };
+        debug_assert!(well_formed(&self.state), "state isn't {}x{}", self.state_width,
+            self.state_height);
+        debug_assert!(well_formed(&self.ever_alive), "ever_alive isn't {}x{}", self.state_width,
+            self.state_height);
+        debug_assert!(self.previous.as_ref().is_none_or(well_formed), "previous isn't {}x{}",
+            self.state_width, self.state_height);
+        debug_assert!(self.state.iter().flatten().zip(self.ever_alive.iter().flatten())
+            .all(|(&alive, &ever)| ever || !alive), "a live cell isn't marked in ever_alive");
+        debug_assert!(self.arena.is_none_or(|arena| arena.x + arena.width <= self.state_width
+            && arena.y + arena.height <= self.state_height), "the arena is off the board");
+        debug_assert!(self.regions.iter().all(|(region, _)| region.x + region.width
+            <= self.state_width && region.y + region.height <= self.state_height),
+            "a region is off the board");
+    }
+
+    // Get the part of the board that's simulated, which is all of it unless there's an arena
+    fn region(&self) -> Rect {
+        return self.arena.unwrap_or(Rect { x: 0, y: 0, width: self.state_width,
+            height: self.state_height });
+    }
+
+    // Move to a next generation computed elsewhere
+    fn advance_to(&mut self, state: Vec<Vec<bool>>) {
+        self.state = state;
+        self.generation += 1;
+        self.mark_ever_alive();
+        self.mark_births();
+        self.check_invariants();
+    }
+
+    // Advance one generation by bringing some cells to life and killing others
+    fn apply_delta(&mut self, births: &[(usize, usize)], deaths: &[(usize, usize)]) {
+        for &(x, y) in births {
+            self.set_cell(x, y, true);
+        }
+        for &(x, y) in deaths {
+            self.set_cell(x, y, false);
+        }
+        self.generation += 1;
+        self.mark_births();
+        self.check_invariants();
+    }
+
+    // Record every currently live cell in `ever_alive`
+    fn mark_ever_alive(&mut self) {
+        for y in 0..self.state_height {
+            for x in 0..self.state_width {
+                if self.state[x][y] {
+                    self.ever_alive[x][y] = true;
+                }
+            }
+        }
+    }
+
+    // Tag the cells that have come to life since the last generation with the current one, while
+    // tracking birth generations. Cells edited to life between generations count as born in the
+    // next one.
+    fn mark_births(&mut self) {
+        if let Some(birth_gen) = &mut self.birth_gen {
+            for x in 0..self.state_width {
+                for y in 0..self.state_height {
+                    if !self.state[x][y] {
+                        birth_gen[x][y] = NOT_BORN;
+                    } else if birth_gen[x][y] == NOT_BORN {
+                        birth_gen[x][y] = self.generation;
+                    }
+                }
+            }
+        }
+    }
+
+    // Start or stop tracking the generation each cell was born in. Cells that are already alive
+    // count as born in the current generation.
+    fn track_births(&mut self, enabled: bool) {
+        self.birth_gen = None;
+        if enabled {
+            self.birth_gen = Some(vec![vec![NOT_BORN; self.state_height]; self.state_width]);
+            self.mark_births();
+        }
+    }
+
+    // Set a single cell's state
+    fn set_cell(&mut self, x: usize, y: usize, alive: bool) {
+        self.state[x][y] = alive;
+        if alive {
+            self.ever_alive[x][y] = true;
+        }
+    }
+
+    // Update every cell from a snapshot of the previous generation
+    fn update_synchronous(&mut self) {
+        self.state = self.next_synchronous();
+    }
+
+    // Get what a synchronous update would turn the state into, without changing anything
+    fn next_synchronous(&self) -> Vec<Vec<bool>> {
+        let mut new_state = self.state.clone();
+        let region = self.region();
+        for y in region.y..region.y + region.height {
+            for x in region.x..region.x + region.width {
+                let neighbors = self.cell_get_neighbors(x as i32, y as i32);
+                new_state[x][y] = self.cell_next_state(x, y, self.state[x][y], neighbors);
+            }
+        }
+        return new_state;
+    }
+
+    // Update by XORing the rule's result with the generation before the current one. Whatever
+    // the rule, this can be undone exactly, since the generation before is the rule's result
+    // XOR the generation after. Cells outside the arena are left alone.
+    fn update_second_order(&mut self) {
+        let mut new_state = self.next_synchronous();
+        let region = self.region();
+        if let Some(previous) = &self.previous {
+            for y in region.y..region.y + region.height {
+                for x in region.x..region.x + region.width {
+                    new_state[x][y] ^= previous[x][y];
+                }
+            }
+        }
+        self.previous = Some(std::mem::replace(&mut self.state, new_state));
+    }
+
+    // Step a second-order game back one generation, which needs no history beyond the current
+    // and previous generations. The generation count stops at 0, though the board can keep going
+    // back. Returns false, changing nothing, if the game isn't second-order.
+    fn step_back(&mut self) -> bool {
+        // Going back is going forward with the two generations swapped, then swapping them back
+        match &mut self.previous {
+            Some(previous) => std::mem::swap(&mut self.state, previous),
+            None => return false,
+        }
+        self.update_second_order();
+        if let Some(previous) = &mut self.previous {
+            std::mem::swap(&mut self.state, previous);
+        }
+        self.generation = self.generation.saturating_sub(1);
+        self.mark_ever_alive();
+
+        // Nothing says when the cells alive again were really born, so they count as born now,
+        // and so do any that were alive all along but tagged with a generation that's now ahead
+        if let Some(birth_gen) = &mut self.birth_gen {
+            for column in birth_gen.iter_mut() {
+                for born in column.iter_mut().filter(|born| **born != NOT_BORN) {
+                    *born = (*born).min(self.generation);
+                }
+            }
+        }
+        self.mark_births();
+        self.check_invariants();
+        return true;
+    }
+
+    // Forget the generation before the current one, as if it were empty, when the state is reset
+    fn forget_previous(&mut self) {
+        if self.previous.is_some() {
+            self.previous = Some(vec![vec![false; self.state_height]; self.state_width]);
+        }
+    }
+
+    // Update every cell from a snapshot of the previous generation, splitting the board into
+    // vertical strips that are computed on separate threads
+    fn update_parallel(&mut self, threads: usize) {
+        let strip_width = ((self.state_width + threads - 1) / threads.max(1)).max(1);
+        let mut new_state = Vec::with_capacity(self.state_width);
+        {
+            let game = &*self;
+            let region = game.region();
+            let strips = (0..game.state_width).step_by(strip_width).map(move |left| {
+                let right = (left + strip_width).min(game.state_width);
+                return (left..right).map(move |x| {
+                    return (0..game.state_height).map(|y| {
+                        if !region.contains(x, y) {
+                            return game.state[x][y];
+                        }
+                        let neighbors = game.cell_get_neighbors(x as i32, y as i32);
+                        return game.cell_next_state(x, y, game.state[x][y], neighbors);
+                    }).collect::<Vec<bool>>();
+                });
+            });
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = strips
+                    .map(|strip| scope.spawn(move || strip.collect::<Vec<Vec<bool>>>()))
+                    .collect();
+                for handle in handles {
+                    new_state.extend(handle.join().unwrap());
+                }
+            });
+        }
+        self.advance_to(new_state);
+    }
+
+    // Update a single cell, reading neighbors that may already have been updated this generation
+    fn update_cell_in_place(&mut self, x: usize, y: usize) {
+        let neighbors = self.cell_get_neighbors(x as i32, y as i32);
+        self.state[x][y] = self.cell_next_state(x, y, self.state[x][y], neighbors);
+    }
+
+    // Apply the rule to a cell with the given state and number of living neighbors
+    fn cell_next_state(&self, x: usize, y: usize, alive: bool, neighbors: u8) -> bool {
+        return self.rule_at(x, y).next_state(alive, neighbors);
+    }
+
+    // Get the rule a cell follows: that of the topmost region it's in, or the board's own
+    fn rule_at(&self, x: usize, y: usize) -> &Rule {
+        return self.regions.iter().rev()
+            .find(|(bounds, _)| bounds.contains(x, y))
+            .map_or(&self.rule, |(_, rule)| rule);
+    }
+
+    // Draw the game state to the screen
+    fn draw(&self, options: &DrawOptions, camera: &Camera) {
+        olc::clear(olc::BLACK);
+        for y in 0..self.state_height {
+            for x in 0..self.state_width {
+                if self.state[x][y] {
+                    camera.draw_cell(x, y, self.live_color(x, y));
+                } else if options.show_heat {
+                    let neighbors = self.cell_get_neighbors(x as i32, y as i32);
+                    if neighbors > 0 {
+                        camera.draw_cell(x, y, HEAT_COLORS[neighbors as usize]);
+                    }
+                } else if options.show_untouched && !self.ever_alive[x][y] {
+                    camera.draw_cell(x, y, UNTOUCHED_COLOR);
+                }
+            }
+        }
+    }
+
+    // Get the color of a live cell: white, or a hue that cycles with the generation it was born
+    // in while tracking births
+    fn live_color(&self, x: usize, y: usize) -> olc::Pixel {
+        return match &self.birth_gen {
+            Some(birth_gen) => {
+                let born = match birth_gen[x][y] {
+                    NOT_BORN => self.generation,
+                    born => born,
+                };
+                let hue = (born % BIRTH_HUE_PERIOD) as f32 * 360.0 / BIRTH_HUE_PERIOD as f32;
+                let (r, g, b) = components::hue_color(hue);
+                olc::Pixel::rgb(r, g, b)
+            },
+            None => olc::WHITE,
+        };
+    }
+
+    // Draw the game state overlaid on another state of the same size. Cells live in both are
+    // white, and cells live in only one are tinted by which one.
+    fn draw_diff(&self, other: &[Vec<bool>], camera: &Camera) {
+        olc::clear(olc::BLACK);
+        for y in 0..self.state_height {
+            for x in 0..self.state_width {
+                match (self.state[x][y], other[x][y]) {
+                    (true, true)   => camera.draw_cell(x, y, olc::WHITE),
+                    (true, false)  => camera.draw_cell(x, y, DIFF_FIRST_COLOR),
+                    (false, true)  => camera.draw_cell(x, y, DIFF_SECOND_COLOR),
+                    (false, false) => {},
+                }
+            }
+        }
+    }
+
+    // Get the number of living neighbors of the specified cell
+    fn cell_get_neighbors(&self, x: i32, y: i32) -> u8 {
+        let mut total = 0;
+        for yofs in -1..=1 {
+            for xofs in -1..=1 {
+                if xofs == 0 && yofs == 0 {
+                    continue;  // Don't count center cell
+                }
+                if let Some((x2, y2)) = self.resolve_coords(x + xofs, y + yofs) {
+                    if self.state[x2][y2] {
+                        total += 1;
+                    }
+                }
+            }
+        }
+        return total;
+    }
+
+    // Map a possibly out-of-bounds coordinate onto the board under the current topology. Returns
+    // None if it falls off a bounded edge. With an arena, its edges are treated as the board's.
+    fn resolve_coords(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        let region = self.region();
+        let width = region.width as i32;
+        let height = region.height as i32;
+        let (x, y) = (x - region.x as i32, y - region.y as i32);
+        let (x, y) = match self.topology {
+            Topology::Bounded  => (x, y),
+            Topology::Torus    => (x.rem_euclid(width), y.rem_euclid(height)),
+            Topology::Cylinder => (x.rem_euclid(width), y),
+            Topology::Reflect  => (reflect_coord(x, width), reflect_coord(y, height)),
+        };
+
+        if (0..width).contains(&x) && (0..height).contains(&y) {
+            return Some((x as usize + region.x, y as usize + region.y));
+        } else {
+            return None;
+        }
+    }
+
+    // Get the state as an RLE pattern
+    fn to_rle(&self) -> String {
+        return rle::encode(&self.state, &self.rule.to_string());
+    }
+
+    // Replace the state with an RLE pattern file, centered on the board, or read it from stdin
+    // if the path is `-`. Returns the pattern's header.
+    fn load_rle(&mut self, path: &str) -> std::io::Result<rle::Header> {
+        if path == "-" {
+            let stdin = std::io::stdin();
+            let lock = stdin.lock();
+            return self.load_rle_reader(lock);
+        }
+        let file = std::fs::File::open(path)?;
+        return self.load_rle_reader(std::io::BufReader::new(file));
+    }
+
+    // Replace the state with an RLE pattern read from `reader`, centered on the board. Cells are
+    // written as they're decoded, so the pattern is never held in memory as a whole. Cells that
+    // don't fit are dropped, or wrap around under torus and cylinder topologies. The pattern's
+    // rule is adopted if it has one. Patterns with cells outside the size in their header are
+    // rejected.
+    fn load_rle_reader<R: BufRead>(&mut self, reader: R) -> std::io::Result<rle::Header> {
+        let invalid_data = |message: String| {
+            return std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+        };
+        let decoder = rle::Decoder::new(reader)?;
+        if let Some(rule) = &decoder.header.rule {
+            self.rule = rule.parse().map_err(invalid_data)?;
+        }
+
+        let header = decoder.header.clone();
+        let limit = i32::MAX as usize;
+        if header.width > limit || header.height > limit {
+            let message = format!("Pattern size {}x{} is too large", header.width, header.height);
+            return Err(invalid_data(message));
+        }
+        let offset_x = (self.state_width as i32 - header.width as i32) / 2;
+        let offset_y = (self.state_height as i32 - header.height as i32) / 2;
+        self.empty_state();
+        for cell in decoder {
+            let (x, y) = cell?;
+            if x >= header.width || y >= header.height {
+                return Err(invalid_data(format!("Pattern runs past the {}x{} size in its header",
+                    header.width, header.height)));
+            }
+            self.set_cell_wrapped(offset_x + x as i32, offset_y + y as i32, true);
+        }
+        return Ok(header);
+    }
+
+    // Advance the given number of generations
+    fn run(&mut self, generations: u64) {
+        for _ in 0..generations {
+            self.update();
+        }
+    }
+
+    // Count the live cells
+    fn population(&self) -> usize {
+        return self.state.iter().flatten().filter(|&&alive| alive).count();
+    }
+
+    // Get the coordinates of every live cell
+    fn live_cells(&self) -> Vec<(i32, i32)> {
+        let mut cells = Vec::new();
+        for y in 0..self.state_height {
+            for x in 0..self.state_width {
+                if self.state[x][y] {
+                    cells.push((x as i32, y as i32));
+                }
+            }
+        }
+        return cells;
+    }
+
+    // Get a hash of the live cells that ignores where they are and how they're oriented. See
+    // `Pattern::canonical_hash`.
+    fn canonical_hash(&self) -> u64 {
+        let pattern = patterns::Pattern { name: String::new(), cells: self.live_cells() };
+        return pattern.canonical_hash();
+    }
+
+    // Save the state to an RLE file
+    fn save_rle(&self, path: &str) -> std::io::Result<()> {
+        return std::fs::write(path, self.to_rle());
+    }
+
+    // Set a cell that may be off the board. Torus and cylinder topologies wrap it onto the board
+    // like neighbor counting does, or around the arena's edges if there is one; otherwise it's
+    // dropped. Returns whether a cell was set.
+    fn set_cell_wrapped(&mut self, x: i32, y: i32, alive: bool) -> bool {
+        let region = self.region();
+        let wrap = |value: i32, start: usize, size: usize| {
+            return (value - start as i32).rem_euclid(size as i32) + start as i32;
+        };
+        let (x, y) = match self.topology {
+            Topology::Torus => {
+                (wrap(x, region.x, region.width), wrap(y, region.y, region.height))
+            },
+            Topology::Cylinder => (wrap(x, region.x, region.width), y),
+            Topology::Bounded | Topology::Reflect => (x, y),
+        };
+
+        let width = self.state_width as i32;
+        let height = self.state_height as i32;
+        if (0..width).contains(&x) && (0..height).contains(&y) {
+            self.set_cell(x as usize, y as usize, alive);
+            return true;
+        } else {
+            return false;
+        }
+    }
+
+    // Set every cell of a `size` by `size` square centered on (x, y), which may be off the board.
+    // Parts past the edges wrap around or are dropped as in `set_cell_wrapped`, so brushes paint
+    // across the seams of a torus or cylinder.
+    fn paint(&mut self, x: i32, y: i32, size: i32, alive: bool) {
+        let (left, top) = (x - size / 2, y - size / 2);
+        for cy in top..top + size {
+            for cx in left..left + size {
+                self.set_cell_wrapped(cx, cy, alive);
+            }
+        }
+    }
+
+    // Move the arena by up to (dx, dy), stopping at the edges of the board. Cells are left where
+    // they are, so the ones it leaves behind freeze and the ones it reaches start to change.
+    // Returns where the arena ended up, or None if there isn't one.
+    fn scroll_arena(&mut self, dx: i32, dy: i32) -> Option<Rect> {
+        let arena = self.arena.as_mut()?;
+        let max_x = (self.state_width - arena.width) as i32;
+        let max_y = (self.state_height - arena.height) as i32;
+        arena.x = (arena.x as i32 + dx).clamp(0, max_x) as usize;
+        arena.y = (arena.y as i32 + dy).clamp(0, max_y) as usize;
+        return Some(*arena);
+    }
+
+    // Move every live cell by (dx, dy). Cells pushed off the board are dropped, or wrap around
+    // under torus and cylinder topologies. With an arena, only the cells inside it move, and the
+    // frozen ones outside stay where they are.
+    fn shift(&mut self, dx: i32, dy: i32) {
+        let region = self.region();
+        let cells: Vec<_> = self.live_cells().into_iter()
+            .filter(|&(x, y)| region.contains(x as usize, y as usize))
+            .collect();
+        for &(x, y) in &cells {
+            self.state[x as usize][y as usize] = false;
+        }
+        for (x, y) in cells {
+            self.set_cell_wrapped(x + dx, y + dy, true);
+        }
+    }
+
+    // Stamp a pattern's live cells onto the board with its top-left corner at (x, y)
+    fn stamp_pattern(&mut self, pattern: &patterns::Pattern, x: i32, y: i32) {
+        for &(cx, cy) in &pattern.cells {
+            self.set_cell_wrapped(x + cx, y + cy, true);
+        }
+    }
+
+    // Get the smallest rectangle containing every live cell, or None if there aren't any
+    fn bounding_box(&self) -> Option<Rect> {
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for y in 0..self.state_height {
+            for x in 0..self.state_width {
+                if self.state[x][y] {
+                    bounds = Some(match bounds {
+                        Some((x1, y1, x2, y2)) => (x1.min(x), y1.min(y), x2.max(x), y2.max(y)),
+                        None => (x, y, x, y),
+                    });
+                }
+            }
+        }
+        return bounds.map(|(x1, y1, x2, y2)| {
+            Rect { x: x1, y: y1, width: x2 - x1 + 1, height: y2 - y1 + 1 }
+        });
+    }
+
+    // Save the live cells to an SVG file, trimmed to their bounding box
+    fn save_svg(&self, path: &str, style: &svg::SvgStyle) -> std::io::Result<()> {
+        let bounds = self.bounding_box().unwrap_or(Rect { x: 0, y: 0, width: 0, height: 0 });
+        return std::fs::write(path, svg::encode(&self.state, bounds, style));
+    }
+
+    // Shrink or grow the board to the given rectangle, which may extend past the current board.
+    // Cells outside the old board start dead.
+    fn crop(&mut self, bounds: Rect) {
+        let kept_width = bounds.width.min(self.state_width.saturating_sub(bounds.x));
+        let kept_height = bounds.height.min(self.state_height.saturating_sub(bounds.y));
+        let copy = |grid: &Vec<Vec<bool>>| -> Vec<Vec<bool>> {
+            let mut cropped = vec![vec![false; bounds.height]; bounds.width];
+            for x in 0..kept_width {
+                for y in 0..kept_height {
+                    cropped[x][y] = grid[bounds.x + x][bounds.y + y];
+                }
+            }
+            return cropped;
+        };
+        self.state = copy(&self.state);
+        self.ever_alive = copy(&self.ever_alive);
+        self.state_width = bounds.width;
+        self.state_height = bounds.height;
+        self.arena = None;
+        self.forget_previous();
+        self.forget_ages();
+        self.track_births(self.birth_gen.is_some());
+
+        // Regions keep their place on the board, cut down to whatever's left of it
+        self.regions = self.regions.iter().filter_map(|&(region, rule)| {
+            let left = region.x.max(bounds.x);
+            let top = region.y.max(bounds.y);
+            let right = (region.x + region.width).min(bounds.x + kept_width);
+            let bottom = (region.y + region.height).min(bounds.y + kept_height);
+            if left >= right || top >= bottom {
+                return None;
+            }
+            let kept = Rect { x: left - bounds.x, y: top - bounds.y, width: right - left,
+                height: bottom - top };
+            return Some((kept, rule));
+        }).collect();
+    }
+
+    // Reset to an empty state
+    fn empty_state(&mut self) {
+        self.state = vec![vec![false; self.state_height]; self.state_width];
+        self.ever_alive = self.state.clone();
+        self.generation = 0;
+        self.forget_previous();
+        self.forget_ages();
+        self.track_births(self.birth_gen.is_some());
+    }
+
+    // Reset to a state with exactly `count` live cells at distinct random positions, within the
+    // arena if there is one. Returns an error if there isn't room for them.
+    fn scatter_cells(&mut self, count: usize) -> Result<(), String> {
+        let region = self.region();
+        let size = region.width * region.height;
+        if count > size {
+            return Err(format!("Can't place {} cells on a board of {} cells", count, size));
+        }
+
+        self.empty_state();
+        for index in rand::seq::index::sample(&mut self.rng, size, count) {
+            self.state[region.x + index % region.width][region.y + index / region.width] = true;
+        }
+        self.ever_alive = self.state.clone();
+        return Ok(());
+    }
+
+    // Set each bit of the state randomly, with `density` being the chance of a cell being alive.
+    // With an arena, only the cells inside it are set.
+    fn randomize_state(&mut self) {
+        self.state = vec![vec![false; self.state_height]; self.state_width];
+        self.generation = 0;
+        let region = self.region();
+        for y in region.y..region.y + region.height {
+            for x in region.x..region.x + region.width {
+                // gen() is in [0, 1), so a density of 0 never sets a cell and 1 always does
+                self.state[x][y] = self.rng.gen::<f64>() < self.density;
+            }
+        }
+        self.ever_alive = self.state.clone();
+        self.forget_previous();
+        self.forget_ages();
+        self.track_births(self.birth_gen.is_some());
+    }
+
+    // Start every cell's age again under --decay-prob, after a reset or a change of size
+    fn forget_ages(&mut self) {
+        if let Some(decay) = &mut self.decay {
+            decay.reset(self.state_width, self.state_height);
+        }
+    }
+}
+
+
+// Mirror a coordinate that's one cell past either end of 0..length back onto the edge cell
+fn reflect_coord(n: i32, length: i32) -> i32 {
+    if n < 0 {
+        return -n - 1;
+    } else if n >= length {
+        return 2 * length - n - 1;
+    } else {
+        return n;
+    }
+}
+
+// Tell the user about an event with the terminal bell and a message, for runs left in the
+// background. Terminals without a bell just show the message.
+fn notify(event: stats::Event, generation: u64) {
+    match event {
+        stats::Event::Extinct => println!("\x07Everything died by generation {}", generation),
+        stats::Event::Stable(1) => println!("\x07Stopped changing at generation {}", generation),
+        stats::Event::Stable(period) => {
+            println!("\x07Settled into a cycle of period {} at generation {}", period, generation);
+        },
+    }
+    let _ = std::io::stdout().flush();
+}
+
+// Utility function to get a command line arg or return a default value
+fn parse_arg<T: std::str::FromStr>(arg_matches: &clap::ArgMatches, arg: &str, default: T) -> T {
+    if let Some(string) = &arg_matches.value_of(arg) {
+        if let Ok(n) = string.parse::<T>() {
+            return n;
+        } else {
+            eprintln!("ERROR: Couldn't parse value for argument `{}`", arg);
+            std::process::exit(1);
+        }
+    } else {
+        return default;
+    }
+}
+
+// Start the metrics server on a port given on the command line
+#[cfg(feature = "metrics")]
+fn start_metrics(application: &mut Application, port: &str) {
+    let port: u16 = port.parse().unwrap_or_else(|_| {
+        eprintln!("ERROR: `{}` isn't a port number", port);
+        std::process::exit(1);
+    });
+    match metrics::serve(port) {
+        Ok((handle, address)) => {
+            println!("Serving metrics at http://{}/metrics", address);
+            application.metrics = Some(handle);
+        },
+        Err(e) => {
+            eprintln!("ERROR: Couldn't serve metrics on port {}: {}", port, e);
+            std::process::exit(1);
+        },
+    }
+}
+
+// Without the server built in, there's nothing to start
+#[cfg(not(feature = "metrics"))]
+fn start_metrics(_: &mut Application, _: &str) {
+    eprintln!("ERROR: --metrics-port needs rustlife to be built with `--features metrics`");
+    std::process::exit(1);
+}
+
+// Shared copy of the game that the panic hook can reach
+type CrashHandle = Arc<Mutex<Option<GameOfLife>>>;
+
+// Install a panic hook that tries to save the board in `handle` to CRASH_FILE before the default
+// hook runs. This is best-effort: if the board can't be reached or written, it's skipped.
+fn install_crash_hook(handle: CrashHandle) {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        use std::io::Write;
+
+        // A poisoned lock still holds the last good snapshot, but a held one means the panic
+        // happened mid-copy, so don't wait on it
+        let snapshot = match handle.try_lock() {
+            Ok(snapshot) => Some(snapshot),
+            Err(std::sync::TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+            Err(std::sync::TryLockError::WouldBlock) => None,
+        };
+        if let Some(game) = snapshot.as_ref().and_then(|snapshot| snapshot.as_ref()) {
+            // Write errors can't be handled here, and stderr may be gone, so ignore all of them
+            let _ = match game.save_rle(CRASH_FILE) {
+                Ok(()) => writeln!(std::io::stderr(), "Saved the board to `{}`", CRASH_FILE),
+                Err(e) => writeln!(std::io::stderr(), "Couldn't save the board: {}", e),
+            };
+        }
+        default_hook(info);
+    }));
+}
+
+fn main() {
+    // Handle command line args
+    let args = clap::App::new("RustLife")
+        .version("0.1.0")
+        .about("A Rust implementation of Conway's Game of Life")
+        .arg(clap::Arg::with_name("width")
+            .short("W")
+            .long("width")
+            .value_name("WIDTH")
+            .help("Sets the simulation space's width")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("height")
+            .short("H")
+            .long("height")
+            .value_name("HEIGHT")
+            .help("Sets the simulation space's height")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("view-width")
+            .long("view-width")
+            .value_name("WIDTH")
+            .help("Sets the window's width in cells, if it should show less than the whole \
+                simulation space [default: --width]")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("view-height")
+            .long("view-height")
+            .value_name("HEIGHT")
+            .help("Sets the window's height in cells, if it should show less than the whole \
+                simulation space [default: --height]")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("scale")
+            .short("S")
+            .long("scale")
+            .value_name("SCALE")
+            .help("Sets the display scale, i.e. how many pixels each cell should take up on the \
+                screen")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("target-visible")
+            .long("target-visible")
+            .value_name("CELLS")
+            .help("Zooms in so about this many cells are visible, whatever the board's size. The \
+                window is --scale times the default size unless --view-width and --view-height \
+                are given.")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("density")
+            .short("d")
+            .long("density")
+            .value_name("DENSITY")
+            .help("Sets the fraction of cells that start alive in a random state, from 0 to 1")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("cells")
+            .long("cells")
+            .value_name("COUNT")
+            .help("Starts with exactly this many live cells at random positions, instead of a \
+                random state based on density")
+            .conflicts_with_all(&["density", "load"])
+            .takes_value(true))
+        .arg(clap::Arg::with_name("seed")
+            .long("seed")
+            .value_name("SEED")
+            .help("Sets the seed used for random states, making them reproducible")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("async")
+            .long("async")
+            .value_name("ORDER")
+            .help("Updates cells one at a time in place instead of all at once, visiting them in \
+                the given order")
+            .possible_values(&["row-major", "random"])
+            .takes_value(true))
+        .arg(clap::Arg::with_name("second-order")
+            .long("second-order")
+            .help("Makes the rule reversible by XORing each generation with the one before it, \
+                starting from an empty one. BACKSPACE then steps backward exactly.")
+            .conflicts_with_all(&["async", "coupled", "play-deltas"]))
+        .arg(clap::Arg::with_name("birth-colors")
+            .long("birth-colors")
+            .help("Colors each live cell by the generation it was born in, cycling through the \
+                hues every 48 generations (also F7)"))
+        .arg(clap::Arg::with_name("decay-prob")
+            .long("decay-prob")
+            .value_name("PROBABILITY")
+            .help("Gives each live cell this much more chance of dying at random for every \
+                generation it's been alive, whatever its neighbors, e.g. 0.01")
+            .takes_value(true)
+            .conflicts_with_all(&["second-order", "coupled", "play-deltas"]))
+        .arg(clap::Arg::with_name("topology")
+            .short("t")
+            .long("topology")
+            .value_name("TOPOLOGY")
+            .help("Sets how the edges of the board behave")
+            .possible_values(&["bounded", "torus", "cylinder", "reflect"])
+            .takes_value(true))
+        .arg(clap::Arg::with_name("arena")
+            .long("arena")
+            .value_name("X,Y,WIDTH,HEIGHT")
+            .help("Only simulates the cells inside this rectangle, treating its edges as the \
+                board's edges under --topology. The rest of the board stays frozen.")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("region")
+            .long("region")
+            .value_name("X,Y,WIDTH,HEIGHT:RULE")
+            .help("Makes the cells in a rectangle follow their own rule, e.g. \
+                `--region 0,0,50,100:B36/S23`. Can be given more than once, and later regions \
+                take precedence where they overlap.")
+            .multiple(true)
+            .number_of_values(1))
+        .arg(clap::Arg::with_name("svg-cell-size")
+            .long("svg-cell-size")
+            .value_name("SIZE")
+            .help("Sets the size of each cell in saved SVG images")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("svg-color")
+            .long("svg-color")
+            .value_name("COLOR")
+            .help("Sets the color of live cells in saved SVG images, as a name or hex code")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("svg-background")
+            .long("svg-background")
+            .value_name("COLOR")
+            .help("Sets the background color of saved SVG images, or `none` for transparent")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("rule")
+            .long("rule")
+            .value_name("RULE")
+            .help("Sets the rule in B/S notation, e.g. B36/S23")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("load")
+            .short("l")
+            .long("load")
+            .value_name("FILE")
+            .help("Starts with an RLE pattern instead of a random state. Use `-` to read the \
+                pattern from stdin.")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("pattern-string")
+            .long("pattern-string")
+            .value_name("CELLS")
+            .help("Starts with a small plaintext pattern instead of a random state, where `O` is \
+                live and `.` is dead. Separate rows with newlines, `\\n` or `|`.")
+            .conflicts_with_all(&["load", "cells", "play-deltas"])
+            .takes_value(true))
+        .arg(clap::Arg::with_name("diff")
+            .long("diff")
+            .value_names(&["FIRST", "SECOND"])
+            .help("Shows two RLE patterns overlaid instead of running a simulation. Cells in both \
+                are white, cells only in the first are red and cells only in the second are blue.")
+            .number_of_values(2)
+            .conflicts_with_all(&["load", "cells", "pattern-string", "play-deltas", "verify",
+                "bench-all", "evolve-toward"]))
+        .arg(clap::Arg::with_name("daily")
+            .long("daily")
+            .help("Starts paused on the seed of the day: a still life or oscillator grown from a \
+                random soup seeded by today's date, so everyone gets the same one each day")
+            .conflicts_with_all(&["load", "cells", "pattern-string", "play-deltas", "diff"]))
+        .arg(clap::Arg::with_name("verify")
+            .long("verify")
+            .value_name("FILE")
+            .help("Runs an RLE pattern without a window and checks its canonical hash against \
+                the one pinned in its `#C rustlife-verify` comment, exiting nonzero on mismatch")
+            .conflicts_with("load")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("expect-hash")
+            .long("expect-hash")
+            .value_name("HASH")
+            .help("Sets the hash --verify expects, overriding the pattern's")
+            .requires("verify")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("export-rule-table")
+            .long("export-rule-table")
+            .value_name("FILE")
+            .help("Writes the rule's next state for every cell state and neighbor count, then \
+                exits. Files ending in `.rule` get a Golly rule table, and anything else CSV."))
+        .arg(clap::Arg::with_name("bench-all")
+            .long("bench-all")
+            .help("Runs the starting state through every backend without a window, then prints \
+                how long each took and whether they all agree")
+            .conflicts_with("verify"))
+        .arg(clap::Arg::with_name("sparse-gc")
+            .long("sparse-gc")
+            .value_name("GENERATIONS")
+            .help("Sets how often the sparse backend gives back memory left over from larger \
+                populations, or 0 for never [default: 256]")
+            .requires("bench-all"))
+        .arg(clap::Arg::with_name("headless-render")
+            .long("headless-render")
+            .value_name("DIR")
+            .help("Saves the starting state and each of the next --generations [default: 100] as \
+                PNG images in a directory, drawn at --scale pixels per cell, without opening a \
+                window")
+            .conflicts_with_all(&["verify", "bench-all", "diff", "play-deltas",
+                "evolve-toward"]))
+        .arg(clap::Arg::with_name("export-html")
+            .long("export-html")
+            .value_name("FILE")
+            .help("Saves a web page that replays the starting state and the next --generations \
+                [default: 200], or the recording given with --play-deltas, without opening a \
+                window")
+            .conflicts_with_all(&["verify", "bench-all", "diff", "headless-render",
+                "evolve-toward"]))
+        .arg(clap::Arg::with_name("evolve-toward")
+            .long("evolve-toward")
+            .value_name("FILE")
+            .help("Searches without a window for small changes to the starting state that bring \
+                it closer to an RLE target pattern after --generations, keeping changes that \
+                reduce the number of differing cells")
+            .conflicts_with_all(&["verify", "bench-all"]))
+        .arg(clap::Arg::with_name("methuselah")
+            .long("methuselah")
+            .value_name("FILE")
+            .help("Runs an RLE pattern without a window until it dies out or settles, for at most \
+                --generations [default: 50000], then reports when it settled, its peak \
+                population and what it left behind")
+            .conflicts_with_all(&["verify", "bench-all", "evolve-toward", "headless-render",
+                "load"]))
+        .arg(clap::Arg::with_name("census")
+            .long("census")
+            .value_name("TRIALS")
+            .help("Runs this many random soups at --density without a window, each until it \
+                settles or for at most --generations [default: 5000], then prints a table of how \
+                they ended")
+            .conflicts_with_all(&["verify", "bench-all", "evolve-toward", "headless-render",
+                "methuselah", "load"]))
+        .arg(clap::Arg::with_name("evolve-steps")
+            .long("evolve-steps")
+            .value_name("COUNT")
+            .help("Sets how many changes --evolve-toward tries [default: 1000]")
+            .requires("evolve-toward"))
+        .arg(clap::Arg::with_name("generations")
+            .long("generations")
+            .value_name("COUNT")
+            .help("Sets how many generations headless modes run for")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("fullscreen")
+            .long("fullscreen")
+            .help("Starts the window full screen"))
+        .arg(clap::Arg::with_name("no-vsync")
+            .long("no-vsync")
+            .help("Draws frames as fast as possible instead of waiting for the display's refresh"))
+        .arg(clap::Arg::with_name("borderless")
+            .long("borderless")
+            .help("Opens the window without a title bar or borders"))
+        .arg(clap::Arg::with_name("window-position")
+            .long("window-position")
+            .value_name("X,Y")
+            .help("Opens the window with its top-left corner at X,Y on the screen, in pixels")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("coupled")
+            .long("coupled")
+            .value_name("STRENGTH")
+            .help("Runs a second, randomly seeded layer whose live cells count as STRENGTH \
+                neighbors each on the first layer, and vice versa")
+            .allow_hyphen_values(true)
+            .takes_value(true))
+        .arg(clap::Arg::with_name("coupled-rule")
+            .long("coupled-rule")
+            .value_name("RULE")
+            .help("Sets the second layer's rule in --coupled mode. Defaults to --rule.")
+            .requires("coupled")
+            .takes_value(true))
+        .arg(clap::Arg::with_name("trails")
+            .long("trails")
+            .help("Fades out cells that die instead of removing them at once"))
+        .arg(clap::Arg::with_name("motion-blur")
+            .long("motion-blur")
+            .value_name("FRAMES")
+            .help("Smears moving cells by fading where they were over this many frames"))
+        .arg(clap::Arg::with_name("flash")
+            .long("flash")
+            .help("Flashes cells that are born"))
+        .arg(clap::Arg::with_name("record-deltas")
+            .long("record-deltas")
+            .value_name("FILE")
+            .help("Records the run to a compact binary file of the cells born and killed each \
+                generation"))
+        .arg(clap::Arg::with_name("play-deltas")
+            .long("play-deltas")
+            .value_name("FILE")
+            .help("Plays back a file from --record-deltas instead of simulating")
+            .conflicts_with_all(&["load", "cells", "coupled", "verify", "bench-all",
+                "record-deltas"]))
+        .arg(clap::Arg::with_name("patterns-dir")
+            .long("patterns-dir")
+            .value_name("DIR")
+            .help("Adds every .rle and .cells file in a directory to the patterns P cycles \
+                through, replacing built-in ones with the same name [default: rustlife_patterns, \
+                if it exists]"))
+        .arg(clap::Arg::with_name("population-csv")
+            .long("population-csv")
+            .value_name("FILE")
+            .help("Logs each generation's population and number of changed cells to a CSV file"))
+        .arg(clap::Arg::with_name("log-entropy")
+            .long("log-entropy")
+            .help("Adds an entropy column to --population-csv, filled in every 8 generations")
+            .requires("population-csv"))
+        .arg(clap::Arg::with_name("hud-corner")
+            .long("hud-corner")
+            .value_name("CORNER")
+            .help("Sets the corner the HUD and other readouts start in [default: top-left]")
+            .possible_values(&["top-left", "top-right", "bottom-right", "bottom-left"])
+            .takes_value(true))
+        .arg(clap::Arg::with_name("bevel")
+            .long("bevel")
+            .help("Draws live cells as beveled tiles with lit and shaded edges. Each cell is \
+                drawn at --scale pixels instead of being scaled up, so the bevel needs a scale of \
+                at least 3."))
+        .arg(clap::Arg::with_name("goe-hint")
+            .long("goe-hint")
+            .help("Highlights 3x3 patches that no previous generation could produce under the \
+                rule. This is a heuristic: it can miss Gardens of Eden, but never flags a patch \
+                that has a predecessor."))
+        .arg(clap::Arg::with_name("notify")
+            .long("notify")
+            .help("Rings the terminal bell when everything dies or the board settles into a \
+                repeating cycle"))
+        .arg(clap::Arg::with_name("max-gen")
+            .long("max-gen")
+            .value_name("GENERATION")
+            .help("Pauses the simulation when it reaches this generation. Press Q to clear the \
+                limit and keep going."))
+        .arg(clap::Arg::with_name("rate")
+            .long("rate")
+            .value_name("GENERATIONS")
+            .help("Sets how many generations run per second [default: 15]"))
+        .arg(clap::Arg::with_name("tick-budget")
+            .long("tick-budget")
+            .value_name("MS")
+            .help("Sets how long each frame may spend simulating before it draws and reads input, \
+                carrying any generations left over to the next frame [default: 8]"))
+        .arg(clap::Arg::with_name("fps-cap")
+            .long("fps-cap")
+            .value_name("FRAMES")
+            .help("Holds the window to this many frames per second, waiting out the rest of each \
+                frame, whatever --rate and vsync are set to"))
+        .arg(clap::Arg::with_name("metrics-port")
+            .long("metrics-port")
+            .value_name("PORT")
+            .help("Serves the generation, population, peak population and real rate for \
+                Prometheus at http://127.0.0.1:PORT/metrics. Needs rustlife to be built with \
+                `--features metrics`."))
+        .arg(clap::Arg::with_name("start-paused")
+            .long("start-paused")
+            .help("Whether to start the simulation paused"))
+        .subcommand(clap::SubCommand::with_name("step")
+            .about("Loads a pattern, advances it and saves the result without opening a window. \
+                The board size, topology and rule come from the main options.")
+            .arg(clap::Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .value_name("FILE")
+                .help("RLE pattern to start from, or - to read it from stdin")
+                .required(true))
+            .arg(clap::Arg::with_name("gens")
+                .short("g")
+                .long("gens")
+                .value_name("GENERATIONS")
+                .help("How many generations to advance [default: 1]"))
+            .arg(clap::Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .value_name("FILE")
+                .help("Where to save the resulting RLE pattern")
+                .required(true))
+            .arg(clap::Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .help("Prints what was loaded and saved")))
+        .get_matches();
+
+    // Open the recording to play back, if any, since it decides the size of the board
+    let player = args.value_of("play-deltas").map(|path| {
+        let player = std::fs::File::open(path)
+            .and_then(|file| deltas::Player::new(std::io::BufReader::new(file)));
+        return player.unwrap_or_else(|e| {
+            eprintln!("ERROR: Couldn't play `{}`: {}", path, e);
+            std::process::exit(1);
+        });
+    });
+
+    // Set screen parameters
+    let screen_width  = player.as_ref()
+        .map_or_else(|| parse_arg(&args, "width",  SCREEN_WIDTH),  |player| player.width as i32);
+    let screen_height = player.as_ref()
+        .map_or_else(|| parse_arg(&args, "height", SCREEN_HEIGHT), |player| player.height as i32);
+    let screen_scale  = parse_arg(&args, "scale",  SCREEN_SCALE);
+    // Zooming to a number of visible cells keeps the window the same size whatever the board's
+    // size, so it should only follow the board when asked to
+    let target_visible = args.is_present("target-visible");
+    let (default_view_width, default_view_height) = if target_visible {
+        (SCREEN_WIDTH, SCREEN_HEIGHT)
+    } else {
+        (screen_width, screen_height)
+    };
+    let view_width    = parse_arg(&args, "view-width",  default_view_width);
+    let view_height   = parse_arg(&args, "view-height", default_view_height);
+
+    // Set simulation parameters
+    let density = parse_arg(&args, "density", DENSITY);
+    if !(0.0..=1.0).contains(&density) {
+        eprintln!("ERROR: Density must be between 0 and 1");
+        std::process::exit(1);
+    }
+    let seed = parse_arg(&args, "seed", rand::random::<u64>());
+
+    // Initialize the application
+    let mut game = GameOfLife::new(screen_width as usize, screen_height as usize, seed);
+    game.density = density;
+    game.update_mode = parse_arg(&args, "async", UpdateMode::Synchronous);
+    game.topology = parse_arg(&args, "topology", Topology::Bounded);
+    if args.is_present("second-order") {
+        game.previous = Some(vec![vec![false; game.state_height]; game.state_width]);
+    }
+    game.track_births(args.is_present("birth-colors"));
+    if args.is_present("decay-prob") {
+        let probability: f64 = parse_arg(&args, "decay-prob", 0.0);
+        if !(0.0..=1.0).contains(&probability) {
+            eprintln!("ERROR: The decay probability must be between 0 and 1");
+            std::process::exit(1);
+        }
+        game.decay = Some(decay::Decay::new(probability, game.state_width, game.state_height));
+    }
+    game.rule = parse_arg(&args, "rule", Rule::life());
+    if let Some(arena) = args.value_of("arena") {
+        let arena: Rect = arena.parse().unwrap_or_else(|e| {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        });
+        if arena.x + arena.width > game.state_width || arena.y + arena.height > game.state_height {
+            eprintln!("ERROR: The arena doesn't fit on the {}x{} board", game.state_width,
+                game.state_height);
+            std::process::exit(1);
+        }
+        game.arena = Some(arena);
+    }
+    for region in args.values_of("region").into_iter().flatten() {
+        let parsed = match region.splitn(2, ':').collect::<Vec<&str>>()[..] {
+            [bounds, rule] => bounds.parse::<Rect>().and_then(|bounds| {
+                rule.parse::<Rule>().map(|rule| (bounds, rule))
+            }),
+            _ => Err(format!("Expected a region as `x,y,width,height:rule`, got `{}`", region)),
+        };
+        let (bounds, rule) = parsed.unwrap_or_else(|e| {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        });
+        if bounds.x + bounds.width > game.state_width
+                || bounds.y + bounds.height > game.state_height {
+            eprintln!("ERROR: The region `{}` doesn't fit on the {}x{} board", region,
+                game.state_width, game.state_height);
+            std::process::exit(1);
+        }
+        game.regions.push((bounds, rule));
+    }
+    // Process a pattern without starting the window
+    if let Some(step_args) = args.subcommand_matches("step") {
+        let input = step_args.value_of("input").unwrap();
+        let output = step_args.value_of("output").unwrap();
+        let generations = parse_arg(step_args, "gens", 1);
+        if let Err(e) = headless::step(&mut game, input, generations, output,
+                step_args.is_present("verbose")) {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        }
+        std::process::exit(0);
+    }
+
+    // Verify a pattern's evolution without starting the window
+    if let Some(path) = args.value_of("verify") {
+        let header = match game.load_rle(path) {
+            Ok(header) => header,
+            Err(e) => {
+                eprintln!("ERROR: Couldn't load pattern `{}`: {}", path, e);
+                std::process::exit(1);
+            },
+        };
+        let mut expectation = headless::parse_expectation(&header.comments).unwrap_or_else(|e| {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        });
+        if let Some(hash) = args.value_of("expect-hash") {
+            expectation.hash = Some(headless::parse_hash(hash).unwrap_or_else(|e| {
+                eprintln!("ERROR: {}", e);
+                std::process::exit(1);
+            }));
+        }
+        let generations = parse_arg(&args, "generations",
+            expectation.generations.unwrap_or(VERIFY_GENERATIONS));
+        let matched = headless::verify(&mut game, generations, expectation.hash);
+        std::process::exit(if matched { 0 } else { 1 });
+    }
+
+    match args.value_of("load") {
+        Some(path) => {
+            let header = game.load_rle(path).unwrap_or_else(|e| {
+                eprintln!("ERROR: Couldn't load pattern `{}`: {}", path, e);
+                std::process::exit(1);
+            });
+            // Pick up where a checkpoint left off, random number generator and all
+            match checkpoint::parse(&header.comments) {
+                Ok(Some((generation, rng))) => {
+                    game.generation = generation;
+                    game.rng = rng.restore();
+                    println!("Resumed a checkpoint at generation {}", generation);
+                },
+                Ok(None) => {},
+                Err(e) => {
+                    eprintln!("ERROR: {} in `{}`", e, path);
+                    std::process::exit(1);
+                },
+            }
+        },
+        None => match args.value_of("cells") {
+            Some(_) => {
+                let count = parse_arg(&args, "cells", 0);
+                if let Err(e) = game.scatter_cells(count) {
+                    eprintln!("ERROR: {}", e);
+                    std::process::exit(1);
+                }
+            },
+            None => match args.value_of("pattern-string") {
+                Some(text) => {
+                    let text = plaintext::unescape(text);
+                    let (pattern, width, height) = plaintext::parse("command line", &text)
+                        .unwrap_or_else(|e| {
+                            eprintln!("ERROR: {}", e);
+                            std::process::exit(1);
+                        });
+                    game.empty_state();
+                    game.stamp_pattern(&pattern, (game.state_width as i32 - width as i32) / 2,
+                        (game.state_height as i32 - height as i32) / 2);
+                    println!("Seeded a {}x{} pattern with {} live cells", width, height,
+                        pattern.cells.len());
+                },
+                None => game.randomize_state(),
+            },
+        },
+    }
+
+    // Grow the seed of the day, and show it paused with its stats
+    let daily = args.is_present("daily");
+    if daily {
+        // Anything smaller would cut off what the soup settled into
+        let size = headless::DAILY_BOARD_SIZE;
+        if game.state_width < size || game.state_height < size {
+            eprintln!("ERROR: The seed of the day needs a board of at least {}x{}", size, size);
+            std::process::exit(1);
+        }
+        let day = headless::today();
+        let (year, month, date) = headless::civil_date(day);
+        match headless::find_daily(&mut game, day) {
+            Some(found) => {
+                let kind = if found.period == 1 {
+                    "a still life".to_string()
+                } else {
+                    format!("an oscillator of period {}", found.period)
+                };
+                println!("Seed of the day for {}-{:02}-{:02}: {}, {} cells, settled after {} \
+                    generations", year, month, date, kind, game.population(), game.generation);
+                println!("Canonical hash {:016x}", game.canonical_hash());
+                if found.attempt > 0 {
+                    println!("({} earlier soups died out or didn't settle)", found.attempt);
+                }
+            },
+            None => {
+                eprintln!("ERROR: No soup settled today. Try a larger --width and --height.");
+                std::process::exit(1);
+            },
+        }
+    }
+
+    // Start from the recording's first frame
+    if let Some(player) = &player {
+        game.rule = player.rule.parse().unwrap_or_else(|e| {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        });
+        game.empty_state();
+        game.state = player.initial.clone();
+        game.mark_ever_alive();
+    }
+
+    // Load both patterns to compare. The board shows the first and the second is kept alongside.
+    let mut diff_against = None;
+    if let Some(paths) = args.values_of("diff") {
+        let paths: Vec<&str> = paths.collect();
+        let mut second = game.clone();
+        for (board, &path) in [&mut game, &mut second].iter_mut().zip(&paths) {
+            if let Err(e) = board.load_rle(path) {
+                eprintln!("ERROR: Couldn't load pattern `{}`: {}", path, e);
+                std::process::exit(1);
+            }
+        }
+        println!("{} cells differ", stats::hamming_distance(&game.state, &second.state));
+        diff_against = Some(second.state);
+    }
+
+    // Write the rule out for other programs, with any rule the pattern brought along
+    if let Some(path) = args.value_of("export-rule-table") {
+        let file = std::path::Path::new(path);
+        let table = match file.extension().and_then(|extension| extension.to_str()) {
+            Some("rule") => {
+                let name = file.file_stem().and_then(|stem| stem.to_str()).unwrap_or("Exported");
+                game.rule.to_golly_table(name)
+            },
+            _ => game.rule.to_csv(),
+        };
+        match std::fs::write(path, table) {
+            Ok(()) => println!("Saved `{}`", path),
+            Err(e) => {
+                eprintln!("ERROR: Couldn't save `{}`: {}", path, e);
+                std::process::exit(1);
+            },
+        }
+        std::process::exit(0);
+    }
+
+    // Benchmark the starting state without starting the window
+    if args.is_present("bench-all") {
+        let generations = parse_arg(&args, "generations", BENCH_GENERATIONS);
+        let gc_interval = parse_arg(&args, "sparse-gc", sparse::DEFAULT_GC_INTERVAL);
+        let agreed = headless::bench_all(&game, generations, gc_interval);
+        std::process::exit(if agreed { 0 } else { 1 });
+    }
+
+    // Render frames to images without starting the window
+    if let Some(dir) = args.value_of("headless-render") {
+        let generations = parse_arg(&args, "generations", RENDER_GENERATIONS);
+        if let Err(e) = headless::render_frames(&mut game, dir, generations,
+                screen_scale.max(1) as usize) {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        }
+        std::process::exit(0);
+    }
+
+    // Save a run as a web page without starting the window. A recording is embedded as it is.
+    if let Some(path) = args.value_of("export-html") {
+        let exported = match args.value_of("play-deltas") {
+            Some(recording) => std::fs::read(recording)
+                .map_err(|e| format!("Couldn't read `{}`: {}", recording, e))
+                .and_then(|stream| {
+                    let title = format!("rustlife: {}", recording);
+                    std::fs::write(path, html::encode(&stream, &title))
+                        .map_err(|e| format!("Couldn't save `{}`: {}", path, e))
+                })
+                .map(|()| println!("Saved `{}` to `{}`", recording, path)),
+            None => {
+                let generations = parse_arg(&args, "generations", HTML_GENERATIONS);
+                headless::export_html(&mut game, path, generations)
+            },
+        };
+        if let Err(e) = exported {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        }
+        std::process::exit(0);
+    }
+
+    // Search for a start that evolves into the target without starting the window
+    if let Some(path) = args.value_of("evolve-toward") {
+        let mut target = game.clone();
+        if let Err(e) = target.load_rle(path) {
+            eprintln!("ERROR: Couldn't load pattern `{}`: {}", path, e);
+            std::process::exit(1);
+        }
+        let generations = parse_arg(&args, "generations", EVOLVE_GENERATIONS);
+        let steps = parse_arg(&args, "evolve-steps", EVOLVE_STEPS);
+        let (distance, flips) = headless::evolve_toward(&game, &target.state, generations, steps,
+            seed);
+        println!("Best distance after {} generations: {}", generations, distance);
+        println!("Flipped cells: {:?}", flips);
+        println!("Run again with --seed {} to reproduce this", seed);
+        std::process::exit(0);
+    }
+
+    // Characterize how long a pattern lives without starting the window
+    if let Some(path) = args.value_of("methuselah") {
+        match game.load_rle(path) {
+            Ok(header) if header.width > game.state_width || header.height > game.state_height => {
+                eprintln!("ERROR: Pattern `{}` is {}x{}, which doesn't fit on the {}x{} board",
+                    path, header.width, header.height, game.state_width, game.state_height);
+                std::process::exit(1);
+            },
+            Ok(_) => {},
+            Err(e) => {
+                eprintln!("ERROR: Couldn't load pattern `{}`: {}", path, e);
+                std::process::exit(1);
+            },
+        }
+        let generations = parse_arg(&args, "generations", METHUSELAH_GENERATIONS);
+        headless::methuselah(&mut game, generations).print();
+        std::process::exit(0);
+    }
+
+    // Characterize what the rule does to random soups without starting the window
+    if args.is_present("census") {
+        let trials: u64 = parse_arg(&args, "census", 0);
+        if trials == 0 {
+            eprintln!("ERROR: A census needs at least one soup");
+            std::process::exit(1);
+        }
+        let generations = parse_arg(&args, "generations", CENSUS_GENERATIONS);
+        println!("{} soups of density {} on a {}x{} board, rule {}", trials, game.density,
+            game.state_width, game.state_height, game.rule);
+        headless::census(&mut game, trials, generations).print();
+        std::process::exit(0);
+    }
+
+    // Set up the second layer, which shares everything but the rule and seed with the first
+    let coupling = match args.value_of("coupled") {
+        Some(_) => {
+            let mut partner = game.clone();
+            partner.rng = ChaCha12Rng::seed_from_u64(seed.wrapping_add(1));
+            partner.rule = parse_arg(&args, "coupled-rule", game.rule);
+            partner.randomize_state();
+            Some(coupled::Coupling { partner: partner, strength: parse_arg(&args, "coupled", 0.0) })
+        },
+        None => None,
+    };
+
+    let mut application = Application::new(game);
+    application.coupling = coupling;
+    application.playback = player;
+    application.diff_against = diff_against;
+
+    // Add the user's patterns to the built-in ones
+    let patterns_dir = args.value_of("patterns-dir");
+    let dir = std::path::Path::new(patterns_dir.unwrap_or(PATTERNS_DIR));
+    if patterns_dir.is_some() || dir.is_dir() {
+        let (patterns, problems) = patterns::load_dir(dir).unwrap_or_else(|e| {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        });
+        for problem in problems {
+            eprintln!("WARNING: {}", problem);
+        }
+        let count = patterns.len();
+        for name in patterns::extend_library(&mut application.library, patterns) {
+            eprintln!("WARNING: `{}` in `{}` replaces the built-in pattern", name, dir.display());
+        }
+        println!("Loaded {} patterns from `{}`", count, dir.display());
+    }
+
+    // Log the population as the simulation runs
+    if let Some(path) = args.value_of("population-csv") {
+        let log = std::fs::File::create(path).and_then(|file| {
+            let mut log = std::io::BufWriter::new(file);
+            let entropy = if args.is_present("log-entropy") { ",entropy" } else { "" };
+            writeln!(log, "generation,population,changed{}", entropy)?;
+            return Ok(log);
+        });
+        match log {
+            Ok(log) => {
+                application.population_log = Some(log);
+                application.log_entropy = args.is_present("log-entropy");
+            },
+            Err(e) => {
+                eprintln!("ERROR: Couldn't log to `{}`: {}", path, e);
+                std::process::exit(1);
+            },
+        }
+    }
+
+    // Record the run from the starting state
+    if let Some(path) = args.value_of("record-deltas") {
+        let recorder = std::fs::File::create(path).and_then(|file| {
+            deltas::Recorder::new(std::io::BufWriter::new(file), &application.game.state,
+                &application.game.rule.to_string())
+        });
+        match recorder {
+            Ok(recorder) => application.recorder = Some(recorder),
+            Err(e) => {
+                eprintln!("ERROR: Couldn't record to `{}`: {}", path, e);
+                std::process::exit(1);
+            },
+        }
+    }
+
+    // Serve the counters for Prometheus, which needs the feature that brings the server in
+    if let Some(port) = args.value_of("metrics-port") {
+        start_metrics(&mut application, port);
+    }
+
+    // Save the board if anything goes wrong
+    let crash_handle: CrashHandle = Arc::new(Mutex::new(None));
+    install_crash_hook(crash_handle.clone());
+    application.crash_handle = Some(crash_handle);
+
+    // Set SVG export style
+    let default_style = svg::SvgStyle::default();
+    application.svg_style.cell_size = parse_arg(&args, "svg-cell-size", default_style.cell_size);
+    if let Some(color) = args.value_of("svg-color") {
+        application.svg_style.live_color = color.to_string();
+    }
+    match args.value_of("svg-background") {
+        Some("none") => application.svg_style.background = None,
+        Some(color) => application.svg_style.background = Some(color.to_string()),
+        None => {},
+    }
+    let colors = std::iter::once(&application.svg_style.live_color)
+        .chain(application.svg_style.background.iter());
+    for color in colors {
+        if !svg::is_valid_color(color) {
+            eprintln!("ERROR: `{}` isn't a valid SVG color", color);
+            std::process::exit(1);
+        }
+    }
+
+    // Set visual effects
+    application.effects.trails = args.is_present("trails");
+    application.effects.flash = args.is_present("flash");
+    if args.is_present("motion-blur") {
+        let frames: u32 = parse_arg(&args, "motion-blur", 0);
+        if frames == 0 {
+            eprintln!("ERROR: Motion blur must last at least one frame");
+            std::process::exit(1);
+        }
+        application.effects.motion_blur = Some(frames);
+    }
+
+    if args.is_present("notify") {
+        application.notifier = Some(stats::Detector::new());
+    }
+    if args.is_present("goe-hint") {
+        application.goe_hint = Some(eden::Hint::new(application.game.rule));
+    }
+    application.hud_corner = parse_arg(&args, "hud-corner", Corner::TopLeft);
+
+    // Beveled cells need real pixels to draw their edges in, and so does zooming to fit a number
+    // of cells, so zoom the camera instead of scaling up the whole screen
+    let (mut pixel_width, mut pixel_height) = (view_width, view_height);
+    let mut pixel_scale = screen_scale;
+    application.bevel = args.is_present("bevel");
+    if application.bevel || target_visible {
+        pixel_width *= screen_scale;
+        pixel_height *= screen_scale;
+        pixel_scale = 1;
+        application.camera.zoom = screen_scale;
+    }
+    if target_visible {
+        let cells: u64 = parse_arg(&args, "target-visible", 0);
+        if cells == 0 {
+            eprintln!("ERROR: The target number of visible cells must be at least 1");
+            std::process::exit(1);
+        }
+        application.camera.zoom = Camera::zoom_for_visible(pixel_width, pixel_height, cells);
+    }
+
+    let rate: f32 = parse_arg(&args, "rate", 1.0 / UPDATE_TIME);
+    if !(rate > 0.0 && rate.is_finite()) {
+        eprintln!("ERROR: The rate must be a positive number of generations per second");
+        std::process::exit(1);
+    }
+    application.update_delta = 1.0 / rate;
+    application.tick_budget = std::time::Duration::from_millis(parse_arg(&args, "tick-budget",
+        TICK_BUDGET_MS));
+    if args.is_present("fps-cap") {
+        let frames: f64 = parse_arg(&args, "fps-cap", 0.0);
+        if !(frames > 0.0 && frames.is_finite()) {
+            eprintln!("ERROR: The frame rate cap must be a positive number of frames per second");
+            std::process::exit(1);
+        }
+        application.frame_limiter = Some(stats::FrameLimiter::new(frames));
+    }
+
+    if args.is_present("max-gen") {
+        application.max_generation = Some(parse_arg(&args, "max-gen", 0));
+    }
+
+    // Start in step mode if specified on the command line
+    if args.is_present("start-paused") {
+        application.step = true;
+    }
+    if daily {
+        application.step = true;
+        application.show_hud = true;
+    }
+
+    // Borders and position are changed once the window is open, since the binding can't ask
+    // for them
+    application.placement.borderless = args.is_present("borderless");
+    if let Some(position) = args.value_of("window-position") {
+        let position = window::parse_position(position).unwrap_or_else(|e| {
+            eprintln!("ERROR: {}", e);
+            std::process::exit(1);
+        });
+        application.placement.position = Some(position);
+    }
+
+    // Start the application
+    let full_screen = args.is_present("fullscreen");  // Cover the whole monitor
+    let vsync = !args.is_present("no-vsync");         // Wait for the display between frames
+    olc::start_with_full_screen_and_vsync(
+        "RustLife",
+        &mut application,
+        pixel_width,
+        pixel_height,
+        pixel_scale,
+        pixel_scale,
+        full_screen,
+        vsync
+    ).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fraction of the board's cells that are alive
+    fn live_fraction(game: &GameOfLife) -> f64 {
+        let live = game.state.iter().flatten().filter(|&&cell| cell).count();
+        return live as f64 / (game.state_width * game.state_height) as f64;
+    }
+
+    #[test]
+    fn randomize_state_honors_density() {
+        // 250,000 cells puts the standard deviation of the live fraction around 0.001
+        for &density in &[0.1, 0.25, 0.5, 0.9] {
+            let mut game = GameOfLife::new(500, 500, 0xC0FFEE);
+            game.density = density;
+            game.randomize_state();
+            let fraction = live_fraction(&game);
+            assert!((fraction - density).abs() < 0.01,
+                "density {} produced live fraction {}", density, fraction);
+        }
+    }
+
+    #[test]
+    fn randomize_state_density_extremes() {
+        let mut game = GameOfLife::new(100, 100, 1);
+        game.density = 0.0;
+        game.randomize_state();
+        assert_eq!(live_fraction(&game), 0.0);
+
+        game.density = 1.0;
+        game.randomize_state();
+        assert_eq!(live_fraction(&game), 1.0);
+    }
+
+    #[test]
+    fn scatter_cells_places_exact_count() {
+        let mut game = GameOfLife::new(30, 20, 3);
+        for &count in &[0, 1, 137, 600] {
+            game.scatter_cells(count).unwrap();
+            assert_eq!(game.live_cells().len(), count);
+        }
+        assert!(game.scatter_cells(601).is_err());
+
+        // The same seed places the cells identically
+        let mut a = GameOfLife::new(30, 20, 9);
+        let mut b = GameOfLife::new(30, 20, 9);
+        a.scatter_cells(50).unwrap();
+        b.scatter_cells(50).unwrap();
+        assert_eq!(a.state, b.state);
+    }
+
+    #[test]
+    fn async_row_major_reads_updated_cells() {
+        // A vertical blinker: under synchronous updates it becomes horizontal
+        let mut sync = GameOfLife::new(5, 5, 0);
+        for y in 1..4 { sync.state[2][y] = true; }
+        let mut sequential = GameOfLife::new(5, 5, 0);
+        sequential.state = sync.state.clone();
+        sequential.update_mode = UpdateMode::RowMajor;
+
+        sync.update();
+        sequential.update();
+        assert!(sync.state[1][2] && sync.state[2][2] && sync.state[3][2]);
+        assert!(!sync.state[2][1] && !sync.state[2][3]);
+
+        // In place, (2, 1) dies before (1, 2) is visited, so (1, 2) only sees two neighbors
+        assert!(!sequential.state[1][2]);
+        assert_ne!(sync.state, sequential.state);
+    }
+
+    #[test]
+    fn async_random_order_is_reproducible() {
+        let mut a = GameOfLife::new(40, 40, 7);
+        let mut b = GameOfLife::new(40, 40, 7);
+        for game in [&mut a, &mut b].iter_mut() {
+            game.update_mode = UpdateMode::Random;
+            game.randomize_state();
+            for _ in 0..5 { game.update(); }
+        }
+        assert_eq!(a.state, b.state);
+    }
+
+    #[test]
+    fn neighbors_at_seam_follow_topology() {
+        // Live cells in the corners opposite (0, 0), plus one across the horizontal seam
+        let mut game = GameOfLife::new(4, 4, 0);
+        game.state[3][3] = true;
+        game.state[3][0] = true;
+        game.state[0][3] = true;
+        game.state[0][0] = true;
+
+        let counts: Vec<u8> = [Topology::Bounded, Topology::Torus, Topology::Cylinder,
+                Topology::Reflect].iter()
+            .map(|&topology| {
+                game.topology = topology;
+                game.cell_get_neighbors(0, 0)
+            })
+            .collect();
+        // Bounded sees nothing, the torus sees all three corners, the cylinder only (3, 0), and
+        // reflect sees (0, 0) mirrored across both edges and the corner
+        assert_eq!(counts, vec![0, 3, 1, 3]);
+    }
+
+    #[test]
+    fn bounding_box_covers_live_cells() {
+        let mut game = GameOfLife::new(10, 10, 0);
+        assert_eq!(game.bounding_box(), None);
+
+        game.state[2][7] = true;
+        game.state[5][3] = true;
+        assert_eq!(game.bounding_box(), Some(Rect { x: 2, y: 3, width: 4, height: 5 }));
+    }
+
+    #[test]
+    fn stamped_gliders_travel_in_their_heading() {
+        for &(dx, dy) in &[(1, 1), (-1, 1), (-1, -1), (1, -1)] {
+            let mut game = GameOfLife::new(20, 20, 0);
+            game.stamp_pattern(&patterns::glider_heading(dx, dy), 8, 8);
+            let before = game.bounding_box().unwrap();
+            for _ in 0..4 { game.update(); }
+            let after = game.bounding_box().unwrap();
+
+            // A glider returns to its starting phase one cell diagonally along every 4 generations
+            assert_eq!((after.width, after.height), (before.width, before.height));
+            assert_eq!(after.x as i32 - before.x as i32, dx, "heading ({}, {})", dx, dy);
+            assert_eq!(after.y as i32 - before.y as i32, dy, "heading ({}, {})", dx, dy);
+        }
+    }
+
+    #[test]
+    fn load_rle_centers_pattern_and_adopts_rule() {
+        let mut game = GameOfLife::new(10, 10, 0);
+        let pattern = "x = 3, y = 3, rule = B36/S23\nbo$2bo$3o!\n";
+        game.load_rle_reader(pattern.as_bytes()).unwrap();
+        assert_eq!(game.bounding_box(), Some(Rect { x: 3, y: 3, width: 3, height: 3 }));
+        assert_eq!(game.rule.to_string(), "B36/S23");
+
+        // Rows longer than the header says, or more of them, are rejected instead of wrapping
+        game.topology = Topology::Torus;
+        assert!(game.load_rle_reader("x = 2, y = 3\nbo$2bo$3o!\n".as_bytes()).is_err());
+        assert!(game.load_rle_reader("x = 3, y = 2\nbo$2bo$3o!\n".as_bytes()).is_err());
+        let huge = format!("x = {}, y = 3\no!\n", usize::MAX);
+        assert!(game.load_rle_reader(huge.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn crop_keeps_cells_inside_bounds() {
+        let mut game = GameOfLife::new(10, 10, 0);
+        game.stamp_pattern(&patterns::glider(), 4, 5);
+        game.crop(game.bounding_box().unwrap());
+        assert_eq!((game.state_width, game.state_height), (3, 3));
+        assert_eq!(game.to_rle(), "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+
+        // Growing the board pads it with dead cells
+        game.crop(Rect { x: 1, y: 0, width: 4, height: 5 });
+        assert_eq!((game.state.len(), game.state[0].len()), (4, 5));
+        assert_eq!(game.bounding_box(), Some(Rect { x: 0, y: 0, width: 2, height: 3 }));
+    }
+
+    #[test]
+    fn arena_confines_the_simulation() {
+        // A blinker outside the arena stays frozen
+        let mut game = GameOfLife::new(30, 20, 0);
+        game.arena = Some(Rect { x: 5, y: 5, width: 10, height: 10 });
+        for x in 20..23 { game.state[x][2] = true; }
+        let frozen = game.state.clone();
+        game.update();
+        assert_eq!(game.state, frozen);
+
+        // A glider inside a torus arena wraps around its edges, never leaving it
+        game.topology = Topology::Torus;
+        game.empty_state();
+        game.stamp_pattern(&patterns::glider(), 11, 11);
+        let hash = game.canonical_hash();
+        for _ in 0..40 {
+            game.update();
+            let cells = game.live_cells();
+            assert!(cells.iter().all(|&(x, y)| (5..15).contains(&x) && (5..15).contains(&y)));
+        }
+        assert_eq!(game.canonical_hash(), hash);
+
+        assert_eq!("5,5,10,10".parse(), Ok(Rect { x: 5, y: 5, width: 10, height: 10 }));
+        assert!("5,5,0,10".parse::<Rect>().is_err());
+        assert!("5,5,10".parse::<Rect>().is_err());
+    }
+
+    #[test]
+    fn shift_drops_or_wraps_at_edges() {
+        let mut game = GameOfLife::new(5, 5, 0);
+        game.state[0][2] = true;
+        game.state[3][2] = true;
+        game.shift(-1, 0);
+        assert_eq!(game.live_cells(), vec![(2, 2)]);
+
+        game.topology = Topology::Torus;
+        game.shift(0, 3);
+        assert_eq!(game.live_cells(), vec![(2, 0)]);
+    }
+
+    #[test]
+    fn brush_wraps_across_the_seam() {
+        let mut game = GameOfLife::new(10, 10, 0);
+        game.paint(0, 0, 3, true);
+        assert_eq!(game.live_cells(), vec![(0, 0), (1, 0), (0, 1), (1, 1)]);
+
+        game.empty_state();
+        game.topology = Topology::Torus;
+        game.paint(0, 0, 3, true);
+        let mut cells = game.live_cells();
+        cells.sort();
+        assert_eq!(cells, vec![(0, 0), (0, 1), (0, 9), (1, 0), (1, 1), (1, 9), (9, 0), (9, 1),
+            (9, 9)]);
+
+        // Erasing wraps the same way
+        game.paint(10, 10, 1, false);
+        assert!(!game.state[0][0] && game.state[9][9]);
+
+        // With an arena, edits wrap around its edges instead of the board's
+        let mut game = GameOfLife::new(30, 20, 0);
+        game.topology = Topology::Torus;
+        game.arena = Some(Rect { x: 5, y: 5, width: 10, height: 10 });
+        game.state[20][2] = true;
+        game.paint(5, 5, 3, true);
+        game.stamp_pattern(&patterns::glider(), 11, 13);
+        game.shift(1, 1);
+        assert!(game.state[20][2]);
+        let cells = game.live_cells();
+        assert_eq!(cells.len(), 15);
+        assert!(cells.iter().all(|&(x, y)| (x, y) == (20, 2)
+            || (5..15).contains(&x) && (5..15).contains(&y)));
+    }
+
+    #[test]
+    fn randomize_state_is_reproducible() {
+        let mut a = GameOfLife::new(50, 50, 42);
+        let mut b = GameOfLife::new(50, 50, 42);
+        a.randomize_state();
+        b.randomize_state();
+        assert_eq!(a.state, b.state);
+    }
+
+    #[test]
+    fn scrolled_arena_freezes_what_it_leaves_behind() {
+        // A lone cell outside the arena is frozen until the arena reaches it
+        let mut game = GameOfLife::new(12, 8, 0);
+        game.arena = Some(Rect { x: 0, y: 0, width: 4, height: 4 });
+        game.state[9][2] = true;
+        game.stamp_pattern(&patterns::glider(), 0, 0);
+        game.run(4);
+        assert!(game.state[9][2]);
+        let scene = game.state.clone();
+
+        // Scrolling stops at the edge, and moves nothing by itself
+        let arena = game.scroll_arena(100, -3).unwrap();
+        assert_eq!(arena, Rect { x: 8, y: 0, width: 4, height: 4 });
+        assert_eq!(game.state, scene);
+        game.update();
+        assert!(!game.state[9][2]);
+
+        // Where the arena was stays as it was when the arena left
+        assert!((0..4).all(|x| (0..4).all(|y| game.state[x][y] == scene[x][y])));
+        assert_eq!(GameOfLife::new(4, 4, 0).scroll_arena(1, 0), None);
+    }
+
+    #[test]
+    fn birth_generations_follow_each_rebirth() {
+        // A blinker's center is born once, while its ends are born again every generation
+        let mut game = GameOfLife::new(5, 5, 0);
+        for x in 1..4 { game.state[x][2] = true; }
+        game.track_births(true);
+        game.run(3);
+        let birth_gen = game.birth_gen.as_ref().unwrap();
+        assert_eq!((birth_gen[2][2], birth_gen[2][1], birth_gen[2][3]), (0, 3, 3));
+        assert_eq!(birth_gen[1][2], NOT_BORN);
+        assert!(game.live_color(2, 2) != game.live_color(2, 1));
+
+        // Resetting starts over, and turning tracking off goes back to white
+        game.randomize_state();
+        let birth_gen = game.birth_gen.as_ref().unwrap();
+        assert!(game.live_cells().iter().all(|&(x, y)| birth_gen[x as usize][y as usize] == 0));
+        game.track_births(false);
+        assert_eq!(game.live_color(0, 0), olc::WHITE);
+
+        // Going back never leaves a cell born after the current generation, or a dead one tagged
+        let mut game = GameOfLife::new(16, 16, 4);
+        game.previous = Some(vec![vec![false; 16]; 16]);
+        game.randomize_state();
+        game.track_births(true);
+        game.run(6);
+        for _ in 0..4 {
+            assert!(game.step_back());
+        }
+        let birth_gen = game.birth_gen.as_ref().unwrap();
+        for x in 0..16 {
+            for y in 0..16 {
+                assert_eq!(birth_gen[x][y] == NOT_BORN, !game.state[x][y]);
+                assert!(!game.state[x][y] || birth_gen[x][y] <= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn comparison_pane_steps_in_lockstep() {
+        let mut game = GameOfLife::new(16, 16, 3);
+        game.randomize_state();
+        let mut application = Application::new(game);
+        assert_eq!(application.toggle_comparison(), Ok(()));
+
+        // Same rule, same result, until one of them is changed
+        application.update_game();
+        let comparison = application.comparison.as_ref().unwrap();
+        assert_eq!(comparison.state, application.game.state);
+        application.game.rule = "B36/S23".parse().unwrap();
+        application.game.state[0][0] = !application.game.state[0][0];
+        for _ in 0..4 {
+            application.update_game();
+        }
+        let comparison = application.comparison.as_ref().unwrap();
+        assert_eq!(comparison.generation, application.game.generation);
+        assert_eq!(comparison.rule, Rule::life());
+        assert!(comparison.state != application.game.state);
+
+        assert_eq!(application.toggle_comparison(), Ok(()));
+        assert!(application.comparison.is_none());
+    }
+
+    #[test]
+    fn regions_follow_their_own_rule_but_count_across_edges() {
+        // A blinker straddling the edge of a Life without Death region: the end outside dies as
+        // it would in Life, the cells inside all survive, and the births inside count the cell
+        // outside as a neighbor
+        let mut game = GameOfLife::new(10, 10, 0);
+        game.regions.push((Rect { x: 5, y: 0, width: 5, height: 10 },
+            "B3/S012345678".parse().unwrap()));
+        for x in 4..7 { game.state[x][5] = true; }
+        game.update();
+        let mut expected = vec![(5, 4), (5, 5), (5, 6), (6, 5)];
+        let mut cells: Vec<(usize, usize)> = game.live_cells().into_iter()
+            .map(|(x, y)| (x as usize, y as usize)).collect();
+        expected.sort();
+        cells.sort();
+        assert_eq!(cells, expected);
+        assert_eq!(game.rule_at(4, 5), &Rule::life());
+
+        // Later regions are on top, and cropping keeps them where they were on the board
+        game.regions.push((Rect { x: 8, y: 8, width: 2, height: 2 }, "B2/S".parse().unwrap()));
+        assert_eq!(game.rule_at(9, 9).to_string(), "B2/S");
+        game.crop(Rect { x: 6, y: 6, width: 3, height: 3 });
+        assert_eq!(game.regions[0].0, Rect { x: 0, y: 0, width: 3, height: 3 });
+        assert_eq!(game.regions[1].0, Rect { x: 2, y: 2, width: 1, height: 1 });
+    }
+
+    #[test]
+    fn second_order_runs_backward_exactly() {
+        for &topology in &[Topology::Bounded, Topology::Torus] {
+            let mut game = GameOfLife::new(40, 30, 11);
+            game.topology = topology;
+            game.previous = Some(vec![vec![false; 30]; 40]);
+            game.randomize_state();
+            let original = game.state.clone();
+            game.run(50);
+            assert_ne!(game.state, original);
+            for _ in 0..50 {
+                assert!(game.step_back());
+            }
+            assert_eq!(game.state, original, "{:?}", topology);
+            assert_eq!(game.previous, Some(vec![vec![false; 30]; 40]));
+            assert_eq!(game.generation, 0);
+        }
+
+        // Ordinary games have nothing to go back to
+        let mut game = GameOfLife::new(8, 8, 0);
+        assert!(!game.step_back());
+    }
 }
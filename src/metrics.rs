@@ -0,0 +1,138 @@
+/* ##############################################
+# Prometheus metrics: the HUD's counters served #
+# over HTTP on a local port from a background   #
+# thread, for graphing long runs. Only built    #
+# with the `metrics` cargo feature.             #
+############################################## */
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Longest a scrape gets to send its request before it's dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Most of a request that's read, which is plenty for the request line
+const MAX_REQUEST: usize = 4096;
+
+// The counters as of the last generation, shared with the server thread
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    pub generation: u64,
+    pub population: usize,
+    pub peak_population: usize,         // Most live cells seen in any generation so far
+    pub updates_per_second: Option<f32>,  // Generations really run per second, once measured
+}
+
+impl Snapshot {
+    // Take down a generation's counters
+    pub fn record(&mut self, generation: u64, population: usize) {
+        self.generation = generation;
+        self.population = population;
+        self.peak_population = self.peak_population.max(population);
+    }
+}
+
+pub type Handle = Arc<Mutex<Snapshot>>;
+
+// Start serving metrics on `port` of the loopback interface, on a thread of its own. Returns the
+// snapshot to keep up to date and the address being served, which has the port picked by the
+// system if `port` is 0.
+pub fn serve(port: u16) -> io::Result<(Handle, SocketAddr)> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    let address = listener.local_addr()?;
+    let handle: Handle = Arc::new(Mutex::new(Snapshot::default()));
+    let shared = handle.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            // One bad scrape shouldn't stop the next
+            if let Err(e) = stream.and_then(|stream| respond(stream, &shared)) {
+                eprintln!("WARNING: Couldn't serve metrics: {}", e);
+            }
+        }
+    });
+    return Ok((handle, address));
+}
+
+// Answer one request, with the metrics for `GET /metrics` and nothing for anything else
+fn respond(mut stream: TcpStream, handle: &Handle) -> io::Result<()> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 512];
+    while !request.windows(4).any(|end| end == b"\r\n\r\n") && request.len() < MAX_REQUEST {
+        let read = stream.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    let request_line = String::from_utf8_lossy(&request);
+    let mut words = request_line.split_whitespace();
+    let response = match (words.next(), words.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            // Copy the snapshot out rather than hold the lock while writing
+            let snapshot = *handle.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let body = encode(&snapshot);
+            format!("HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\
+                Content-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)
+        },
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    };
+    return stream.write_all(response.as_bytes());
+}
+
+// Write a snapshot in the Prometheus text format. The rate is left out until it's measured.
+pub fn encode(snapshot: &Snapshot) -> String {
+    let mut output = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+        output += &format!("# HELP {} {}\n# TYPE {} {}\n{} {}\n", name, help, name, kind, name,
+            value);
+    };
+    metric("rustlife_generation", "gauge", "Generations since the last reset.",
+        snapshot.generation.to_string());
+    metric("rustlife_population", "gauge", "Live cells.", snapshot.population.to_string());
+    metric("rustlife_peak_population", "gauge", "Most live cells in any generation so far.",
+        snapshot.peak_population.to_string());
+    if let Some(rate) = snapshot.updates_per_second {
+        metric("rustlife_updates_per_second", "gauge", "Generations really run per second.",
+            rate.to_string());
+    }
+    return output;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_the_latest_snapshot() {
+        let (handle, address) = serve(0).unwrap();
+        {
+            let mut snapshot = handle.lock().unwrap();
+            snapshot.record(3, 40);
+            snapshot.record(4, 25);
+            snapshot.updates_per_second = Some(14.5);
+        }
+
+        let scrape = |path: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = scrape("/metrics");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\nrustlife_generation 4\n"));
+        assert!(response.contains("\nrustlife_population 25\n"));
+        assert!(response.contains("\nrustlife_peak_population 40\n"));
+        assert!(response.contains("\nrustlife_updates_per_second 14.5\n"));
+        assert!(scrape("/").starts_with("HTTP/1.1 404"));
+
+        // No rate until one's been measured
+        assert!(!encode(&Snapshot::default()).contains("updates_per_second"));
+    }
+}
@@ -0,0 +1,104 @@
+/* ############################################
+# Scaled-down overview of the whole board in  #
+# a corner of the screen. Clicking it moves   #
+# the camera there.                           #
+############################################ */
+
+use camera::Camera;
+use olc_pixel_game_engine as olc;
+
+// Longest side of the minimap, in pixels
+const MINIMAP_SIZE: usize = 48;
+
+// Gap between the minimap and the edges of the screen
+const MINIMAP_MARGIN: i32 = 2;
+
+// Minimap colors
+const MINIMAP_BACKGROUND: olc::Pixel = olc::VERY_DARK_GREY;
+const MINIMAP_LIVE_COLOR: olc::Pixel = olc::WHITE;
+const MINIMAP_VIEW_COLOR: olc::Pixel = olc::YELLOW;
+
+// Where the minimap sits on the screen, and how many cells across each of its pixels covers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Minimap {
+    pub left:   i32,
+    pub top:    i32,
+    pub width:  i32,
+    pub height: i32,
+    pub scale:  usize,
+}
+
+impl Minimap {
+    // Lay out a minimap of a board of the given size in the top-right corner of the screen
+    pub fn new(board_width: usize, board_height: usize, screen_width: i32) -> Self {
+        let scale = board_width.max(board_height).div_ceil(MINIMAP_SIZE).max(1);
+        let width = board_width.div_ceil(scale) as i32;
+        let height = board_height.div_ceil(scale) as i32;
+        return Minimap {
+            left: screen_width - width - MINIMAP_MARGIN,
+            top: MINIMAP_MARGIN,
+            width: width,
+            height: height,
+            scale: scale,
+        };
+    }
+
+    // Check whether a screen position is on the minimap
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        return (self.left..self.left + self.width).contains(&x)
+            && (self.top..self.top + self.height).contains(&y);
+    }
+
+    // Get the board cell in the middle of the minimap pixel at a screen position
+    pub fn cell_at(&self, x: i32, y: i32) -> (i32, i32) {
+        let scale = self.scale as i32;
+        return ((x - self.left) * scale + scale / 2, (y - self.top) * scale + scale / 2);
+    }
+
+    // Draw the board's live cells, with an outline around the part of it that's on screen
+    pub fn draw(&self, state: &[Vec<bool>], camera: &Camera) {
+        olc::fill_rect(self.left, self.top, self.width, self.height, MINIMAP_BACKGROUND);
+        for (x, column) in state.iter().enumerate() {
+            for (y, &alive) in column.iter().enumerate() {
+                if alive {
+                    let (x, y) = ((x / self.scale) as i32, (y / self.scale) as i32);
+                    olc::draw(self.left + x, self.top + y, MINIMAP_LIVE_COLOR);
+                }
+            }
+        }
+
+        // Clip the view's outline to the minimap so it doesn't spill over the main view
+        let scale = self.scale as i32;
+        let (view_x, view_y) = camera.cell_at(0, 0);
+        let view_width = olc::screen_width() / camera.zoom / scale + 1;
+        let view_height = olc::screen_height() / camera.zoom / scale + 1;
+        let left = view_x.div_euclid(scale).max(0);
+        let top = view_y.div_euclid(scale).max(0);
+        let right = (view_x.div_euclid(scale) + view_width).min(self.width) - 1;
+        let bottom = (view_y.div_euclid(scale) + view_height).min(self.height) - 1;
+        if left <= right && top <= bottom {
+            olc::draw_rect(self.left + left, self.top + top, right - left, bottom - top,
+                MINIMAP_VIEW_COLOR);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_and_hit_testing() {
+        // A 480x240 board is shown at 10 cells per pixel in the top-right corner
+        let minimap = Minimap::new(480, 240, 200);
+        assert_eq!(minimap, Minimap { left: 150, top: 2, width: 48, height: 24, scale: 10 });
+        assert!(minimap.contains(150, 2) && minimap.contains(197, 25));
+        assert!(!minimap.contains(149, 2) && !minimap.contains(198, 2));
+        assert!(!minimap.contains(150, 26));
+        assert_eq!(minimap.cell_at(150, 2), (5, 5));
+        assert_eq!(minimap.cell_at(197, 25), (475, 235));
+
+        // Small boards aren't blown up
+        assert_eq!(Minimap::new(20, 10, 200).scale, 1);
+    }
+}
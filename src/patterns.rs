@@ -0,0 +1,352 @@
+/* #####################################
+# Library of well-known patterns.      #
+# Cells are (x, y) offsets from the    #
+# pattern's top-left corner.           #
+##################################### */
+
+use std::collections::HashMap;
+use std::path::Path;
+use plaintext;
+use rle;
+
+// Patterns the recognizer knows by name, drawn as rows. Every phase of an oscillator or
+// spaceship needs its own entry unless it's a rotation or reflection of one already here. Add a
+// line to teach it another pattern.
+const KNOWN_PATTERNS: &[(&str, &[&str])] = &[
+    ("block",   &["OO", "OO"]),
+    ("beehive", &[".OO.", "O..O", ".OO."]),
+    ("loaf",    &[".OO.", "O..O", ".O.O", "..O."]),
+    ("boat",    &["OO.", "O.O", ".O."]),
+    ("ship",    &["OO.", "O.O", ".OO"]),
+    ("tub",     &[".O.", "O.O", ".O."]),
+    ("pond",    &[".OO.", "O..O", "O..O", ".OO."]),
+    ("blinker", &["OOO"]),
+    ("toad",    &[".OOO", "OOO."]),
+    ("toad",    &["..O.", "O..O", "O..O", ".O.."]),
+    ("glider",  &[".O.", "..O", "OOO"]),
+    ("glider",  &["O.O", ".OO", ".O."]),
+    ("LWSS",    &[".O..O", "O....", "O...O", "OOOO."]),
+    ("LWSS",    &["..OO.", "OO.OO", "OOOO.", ".OO.."]),
+];
+
+// A named pattern, stored as the offsets of its live cells
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    pub name: String,
+    pub cells: Vec<(i32, i32)>,
+}
+
+impl Pattern {
+    // Create a pattern from rows of text, where `O` is a live cell and anything else is dead
+    pub fn from_rows(name: &str, rows: &[&str]) -> Self {
+        let mut cells = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == 'O' {
+                    cells.push((x as i32, y as i32));
+                }
+            }
+        }
+        return Pattern { name: name.to_string(), cells: cells };
+    }
+
+    // Width of the pattern's bounding box
+    pub fn width(&self) -> i32 {
+        return self.cells.iter().map(|&(x, _)| x + 1).max().unwrap_or(0);
+    }
+
+    // Height of the pattern's bounding box
+    pub fn height(&self) -> i32 {
+        return self.cells.iter().map(|&(_, y)| y + 1).max().unwrap_or(0);
+    }
+
+    // Get a copy of the pattern rotated 90 degrees clockwise (as seen on screen, where y points
+    // down), moved back so its top-left corner is at the origin
+    pub fn rotated(&self) -> Self {
+        let height = self.height();
+        let cells = self.cells.iter().map(|&(x, y)| (height - 1 - y, x)).collect();
+        return Pattern { name: self.name.clone(), cells: cells };
+    }
+
+    // Get a hash of the pattern that's the same wherever it is and however it's rotated or
+    // reflected. It's stable across runs and platforms, so it can be stored and compared later.
+    pub fn canonical_hash(&self) -> u64 {
+        // Take the smallest of the pattern's 8 orientations as its canonical form
+        let canonical = (0..8)
+            .map(|orientation| {
+                let mut cells: Vec<(i32, i32)> = self.cells.iter()
+                    .map(|&(x, y)| if orientation >= 4 { (-x, y) } else { (x, y) })
+                    .map(|(x, y)| match orientation % 4 {
+                        0 => (x, y),
+                        1 => (-y, x),
+                        2 => (-x, -y),
+                        _ => (y, -x),
+                    })
+                    .collect();
+                let min_x = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+                let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+                for cell in cells.iter_mut() {
+                    *cell = (cell.0 - min_x, cell.1 - min_y);
+                }
+                cells.sort();
+                return cells;
+            })
+            .min()
+            .unwrap_or_default();
+
+        // 64-bit FNV-1a over the cell coordinates
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for (x, y) in canonical {
+            for byte in x.to_le_bytes().iter().chain(y.to_le_bytes().iter()) {
+                hash ^= *byte as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        return hash;
+    }
+
+    // Get a copy of the pattern rotated clockwise by the given number of quarter turns
+    pub fn rotated_by(&self, quarter_turns: u32) -> Self {
+        let mut pattern = self.clone();
+        for _ in 0..quarter_turns % 4 {
+            pattern = pattern.rotated();
+        }
+        return pattern;
+    }
+}
+
+// A glider travelling down and to the right, one cell diagonally every 4 generations
+pub fn glider() -> Pattern {
+    return Pattern::from_rows("Glider", &[
+        ".O.",
+        "..O",
+        "OOO",
+    ]);
+}
+
+// Get a glider oriented to travel in the given diagonal direction, where `dx` and `dy` are each
+// -1 or 1
+pub fn glider_heading(dx: i32, dy: i32) -> Pattern {
+    // Each clockwise quarter turn takes the direction (dx, dy) to (-dy, dx)
+    let quarter_turns = match (dx > 0, dy > 0) {
+        (true, true)   => 0,  // Down-right
+        (false, true)  => 1,  // Down-left
+        (false, false) => 2,  // Up-left
+        (true, false)  => 3,  // Up-right
+    };
+    return glider().rotated_by(quarter_turns);
+}
+
+// Get the patterns that can be picked for placing on the board, in the order they're cycled
+// through
+pub fn library() -> Vec<Pattern> {
+    return vec![
+        glider(),
+        Pattern::from_rows("Block", &["OO", "OO"]),
+        Pattern::from_rows("Blinker", &["OOO"]),
+        Pattern::from_rows("Lightweight spaceship", &[
+            ".O..O",
+            "O....",
+            "O...O",
+            "OOOO.",
+        ]),
+        Pattern::from_rows("R-pentomino", &[
+            ".OO",
+            "OO.",
+            ".O.",
+        ]),
+        Pattern::from_rows("Acorn", &[
+            ".O.....",
+            "...O...",
+            "OO..OOO",
+        ]),
+    ];
+}
+
+// Read every `.rle` and `.cells` file in a directory as a pattern, in order of file name. Each is
+// named by its `#N` or `!Name:` line if it has one, or else after its file. Returns the patterns
+// and a message for each file that couldn't be read.
+pub fn load_dir(dir: &Path) -> Result<(Vec<Pattern>, Vec<String>), String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| format!("Couldn't read `{}`: {}", dir.display(), e))?;
+    let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| matches!(path.extension().and_then(|ext| ext.to_str()),
+            Some("rle") | Some("cells")))
+        .collect();
+    paths.sort();
+
+    let (mut patterns, mut problems) = (Vec::new(), Vec::new());
+    for path in paths {
+        match load_file(&path) {
+            Ok(pattern) => patterns.push(pattern),
+            Err(e) => problems.push(format!("Skipped `{}`: {}", path.display(), e)),
+        }
+    }
+    return Ok((patterns, problems));
+}
+
+// Read a single `.rle` or `.cells` file as a pattern
+fn load_file(path: &Path) -> Result<Pattern, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("Untitled");
+    if path.extension().and_then(|ext| ext.to_str()) == Some("cells") {
+        let name = text.lines()
+            .find_map(|line| line.strip_prefix("!Name:"))
+            .map_or(stem, |name| name.trim());
+        return plaintext::parse(name, &text).map(|(pattern, _, _)| pattern);
+    }
+
+    let decoder = rle::Decoder::new(text.as_bytes()).map_err(|e| e.to_string())?;
+    let name = decoder.header.comments.iter()
+        .find_map(|comment| comment.strip_prefix("#N"))
+        .map_or(stem.to_string(), |name| name.trim().to_string());
+    let cells = decoder.map(|cell| cell.map(|(x, y)| (x as i32, y as i32)))
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+    if cells.is_empty() {
+        return Err("The pattern has no cells".to_string());
+    }
+    return Ok(Pattern { name: name, cells: cells });
+}
+
+// Add patterns to the end of a library. One with the same name as a pattern already there,
+// ignoring case, replaces it instead. Returns the names that were replaced.
+pub fn extend_library(library: &mut Vec<Pattern>, patterns: Vec<Pattern>) -> Vec<String> {
+    let mut replaced = Vec::new();
+    for pattern in patterns {
+        match library.iter_mut().find(|other| other.name.eq_ignore_ascii_case(&pattern.name)) {
+            Some(other) => {
+                replaced.push(other.name.clone());
+                *other = pattern;
+            },
+            None => library.push(pattern),
+        }
+    }
+    return replaced;
+}
+
+// Names of known patterns, looked up by canonical hash so they're recognized wherever they are
+// and however they're turned
+pub struct Dictionary {
+    names: HashMap<u64, &'static str>,
+}
+
+impl Dictionary {
+    // Build the dictionary of every pattern in `KNOWN_PATTERNS`
+    pub fn known() -> Self {
+        let names = KNOWN_PATTERNS.iter()
+            .map(|&(name, rows)| (Pattern::from_rows(name, rows).canonical_hash(), name))
+            .collect();
+        return Dictionary { names: names };
+    }
+
+    // Get the name of a pattern made of the given cells, if it's a known one
+    pub fn name(&self, cells: &[(i32, i32)]) -> Option<&'static str> {
+        let pattern = Pattern { name: String::new(), cells: cells.to_vec() };
+        return self.names.get(&pattern.canonical_hash()).cloned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotating_four_times_is_identity() {
+        let pattern = glider();
+        let mut rotated = pattern.rotated().rotated().rotated().rotated().cells;
+        let mut original = pattern.cells.clone();
+        rotated.sort();
+        original.sort();
+        assert_eq!(rotated, original);
+    }
+
+    #[test]
+    fn library_patterns_are_distinct() {
+        let library = library();
+        for (i, a) in library.iter().enumerate() {
+            assert!(!a.cells.is_empty());
+            assert!(library[i + 1..].iter().all(|b| a.canonical_hash() != b.canonical_hash()));
+        }
+    }
+
+    #[test]
+    fn user_patterns_extend_and_override_the_library() {
+        let dir = std::env::temp_dir().join(format!("rustlife_patterns_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a_domino.cells"), "!Name: Domino\nOO\n").unwrap();
+        std::fs::write(dir.join("b_glider.rle"), "#N glider\nx = 3, y = 1\n3o!\n").unwrap();
+        std::fs::write(dir.join("c_unnamed.rle"), "x = 2, y = 2\n2o$2o!\n").unwrap();
+        std::fs::write(dir.join("d_broken.rle"), "no header here").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a pattern").unwrap();
+        let (patterns, problems) = load_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let names: Vec<&str> = patterns.iter().map(|pattern| pattern.name.as_str()).collect();
+        assert_eq!(names, vec!["Domino", "glider", "c_unnamed"]);
+        assert_eq!(patterns[1].cells, vec![(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(problems.len(), 1);
+
+        let mut library = library();
+        let built_in = library.len();
+        assert_eq!(extend_library(&mut library, patterns), vec!["Glider".to_string()]);
+        assert_eq!(library.len(), built_in + 2);
+        assert_eq!(library[0].cells.len(), 3);
+        assert_eq!(library[built_in].name, "Domino");
+    }
+
+    #[test]
+    fn dictionary_recognizes_every_phase() {
+        let dictionary = Dictionary::known();
+        assert_eq!(dictionary.names.len(), KNOWN_PATTERNS.len());
+
+        // Each phase of a glider, turned any way
+        let mut game = ::GameOfLife::new(12, 12, 0);
+        game.stamp_pattern(&glider().rotated_by(3), 3, 3);
+        for _ in 0..4 {
+            assert_eq!(dictionary.name(&game.live_cells()), Some("glider"));
+            game.update();
+        }
+        assert_eq!(dictionary.name(&[(5, 0), (5, 1), (5, 2)]), Some("blinker"));
+
+        // And of the other oscillators and spaceships with several entries
+        let moving: [(&str, &[&str]); 2] = [
+            ("toad", &[".OOO", "OOO."]),
+            ("LWSS", &[".O..O", "O....", "O...O", "OOOO."]),
+        ];
+        for &(name, rows) in &moving {
+            game.empty_state();
+            game.stamp_pattern(&Pattern::from_rows(name, rows), 4, 4);
+            for _ in 0..4 {
+                assert_eq!(dictionary.name(&game.live_cells()), Some(name));
+                game.update();
+            }
+        }
+        assert_eq!(dictionary.name(&[(0, 0), (1, 1)]), None);
+    }
+
+    #[test]
+    fn canonical_hash_ignores_position_and_orientation() {
+        let hash = glider().canonical_hash();
+        for quarter_turns in 1..4 {
+            assert_eq!(glider().rotated_by(quarter_turns).canonical_hash(), hash);
+        }
+        let mirrored = Pattern::from_rows("Glider", &["OOO", "..O", ".O."]);
+        assert_eq!(mirrored.canonical_hash(), hash);
+        let moved = Pattern {
+            name: "Glider".to_string(),
+            cells: glider().cells.iter().map(|&(x, y)| (x + 7, y - 3)).collect(),
+        };
+        assert_eq!(moved.canonical_hash(), hash);
+
+        let other = Pattern::from_rows("Line", &["OOOOO"]);
+        assert_ne!(other.canonical_hash(), hash);
+    }
+
+    #[test]
+    fn rotating_swaps_dimensions() {
+        let pattern = Pattern::from_rows("Line", &["OOO"]).rotated();
+        assert_eq!((pattern.width(), pattern.height()), (1, 3));
+        assert_eq!(pattern.cells, vec![(0, 0), (0, 1), (0, 2)]);
+    }
+}
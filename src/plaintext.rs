@@ -0,0 +1,61 @@
+/* ##########################################
+# Plaintext (.cells) pattern parsing.       #
+# Each line is a row of cells, where `O` is #
+# live and `.` is dead.                     #
+########################################## */
+
+use patterns::Pattern;
+
+// Parse a pattern in the plaintext format. Lines starting with `!` are comments, and `*` is also
+// accepted as a live cell. Returns the pattern and the width and height of its grid, which count
+// dead cells around the edges that the pattern itself doesn't keep.
+pub fn parse(name: &str, text: &str) -> Result<(Pattern, usize, usize), String> {
+    let mut rows = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if line.starts_with('!') {
+            continue;
+        }
+        if let Some(c) = line.chars().find(|&c| !matches!(c, 'O' | 'o' | '*' | '.')) {
+            return Err(format!("Unexpected `{}` on line {} of the pattern", c, number + 1));
+        }
+        rows.push(line);
+    }
+    if rows.iter().all(|row| row.is_empty()) {
+        return Err("The pattern has no cells".to_string());
+    }
+
+    let mut cells = Vec::new();
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            if c != '.' {
+                cells.push((x as i32, y as i32));
+            }
+        }
+    }
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    return Ok((Pattern { name: name.to_string(), cells: cells }, width, rows.len()));
+}
+
+// Turn a pattern typed on the command line into plaintext by splitting rows at `|` or at a
+// literal `\n`, as typed in shells that don't expand it
+pub fn unescape(text: &str) -> String {
+    return text.replace("\\n", "\n").replace('|', "\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_keeps_padding_and_skips_comments() {
+        let (pattern, width, height) = parse("block", "!Name: Block\n....\n.OO.\n.OO.\n....\n")
+            .unwrap();
+        assert_eq!(pattern.cells, vec![(1, 1), (2, 1), (1, 2), (2, 2)]);
+        assert_eq!((width, height), (4, 4));
+
+        assert_eq!(unescape("....\\n.OO.|.OO."), "....\n.OO.\n.OO.");
+        assert!(parse("bad", ".O.\n.X.").is_err());
+        assert!(parse("empty", "!Nothing here").is_err());
+    }
+}
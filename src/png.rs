@@ -0,0 +1,167 @@
+/* ###############################################
+# Minimal PNG encoder for rendering boards to    #
+# images without a window. The image data is     #
+# stored uncompressed, which keeps this short at #
+# the cost of larger files.                      #
+############################################### */
+
+use camera::Camera;
+
+// Every PNG file starts with these bytes
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+// Longest block of uncompressed data deflate can hold
+const MAX_STORED_BLOCK: usize = 65535;
+
+// Render a board (indexed as `state[x][y]`) to RGB pixels, drawing each cell as a `scale` by
+// `scale` square. Live cells are white and dead ones black, as in the window. Returns the
+// image's width, height and pixels, row by row.
+pub fn render(state: &[Vec<bool>], scale: usize) -> (usize, usize, Vec<u8>) {
+    let width = state.len() * scale;
+    let height = state.first().map_or(0, |column| column.len()) * scale;
+    let mut pixels = vec![0; width * height * 3];
+    for y in 0..height {
+        for x in 0..width {
+            if state[x / scale][y / scale] {
+                let index = (y * width + x) * 3;
+                pixels[index..index + 3].copy_from_slice(&[255, 255, 255]);
+            }
+        }
+    }
+    return (width, height, pixels);
+}
+
+// Render what a camera shows on a screen of the given size, one image pixel per screen pixel, so
+// a zoomed-in view comes out as it looks in the window. Anything off the board is black.
+pub fn render_view(state: &[Vec<bool>], camera: &Camera, width: usize, height: usize) -> Vec<u8> {
+    let mut pixels = vec![0; width * height * 3];
+    for y in 0..height {
+        for x in 0..width {
+            let (cell_x, cell_y) = camera.cell_at(x as i32, y as i32);
+            let on_board = cell_x >= 0 && cell_y >= 0;
+            let alive = on_board && state.get(cell_x as usize)
+                .and_then(|column| column.get(cell_y as usize))
+                .cloned()
+                .unwrap_or(false);
+            if alive {
+                let index = (y * width + x) * 3;
+                pixels[index..index + 3].copy_from_slice(&[255, 255, 255]);
+            }
+        }
+    }
+    return pixels;
+}
+
+// Encode 8-bit RGB pixels, row by row, as a PNG file
+pub fn encode_rgb(width: usize, height: usize, pixels: &[u8]) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&(width as u32).to_be_bytes());
+    header.extend_from_slice(&(height as u32).to_be_bytes());
+    // 8 bits per channel, RGB, deflate, standard filtering, no interlacing
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    // Every row starts with its filter type, which is always none here
+    let mut raw = Vec::with_capacity((width * 3 + 1) * height);
+    for row in pixels.chunks(width * 3).take(height) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut file = SIGNATURE.to_vec();
+    write_chunk(&mut file, b"IHDR", &header);
+    write_chunk(&mut file, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut file, b"IEND", &[]);
+    return file;
+}
+
+// Append a chunk: its length, type, data and the CRC of the type and data
+fn write_chunk(file: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    file.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = file.len();
+    file.extend_from_slice(kind);
+    file.extend_from_slice(data);
+    let crc = crc32(&file[start..]);
+    file.extend_from_slice(&crc.to_be_bytes());
+}
+
+// Wrap data in a zlib stream of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = if data.is_empty() {
+        vec![&[]]
+    } else {
+        data.chunks(MAX_STORED_BLOCK).collect()
+    };
+    for (i, block) in blocks.iter().enumerate() {
+        stream.push(if i + 1 == blocks.len() { 1 } else { 0 });
+        let length = block.len() as u16;
+        stream.extend_from_slice(&length.to_le_bytes());
+        stream.extend_from_slice(&(!length).to_le_bytes());
+        stream.extend_from_slice(block);
+    }
+    stream.extend_from_slice(&adler32(data).to_be_bytes());
+    return stream;
+}
+
+// CRC-32 as PNG uses it
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    return !crc;
+}
+
+// Adler-32 checksum of the uncompressed data, which ends a zlib stream
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    return (b << 16) | a;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_a_well_formed_png() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+
+        let state = vec![vec![true, false], vec![false, false], vec![false, true]];
+        let (width, height, pixels) = render(&state, 2);
+        assert_eq!((width, height), (6, 4));
+        assert_eq!(&pixels[0..6], &[255, 255, 255, 255, 255, 255]);
+        assert_eq!(&pixels[6..9], &[0, 0, 0]);
+
+        let file = encode_rgb(width, height, &pixels);
+        assert_eq!(&file[0..8], &SIGNATURE);
+        assert_eq!(&file[12..16], b"IHDR");
+        assert_eq!(&file[16..24], &[0, 0, 0, 6, 0, 0, 0, 4]);
+        assert_eq!(&file[file.len() - 12..], &[0, 0, 0, 0, b'I', b'E', b'N', b'D',
+            0xae, 0x42, 0x60, 0x82]);
+    }
+
+    #[test]
+    fn render_view_matches_the_camera() {
+        let mut state = vec![vec![false; 4]; 4];
+        state[1][2] = true;
+        let camera = Camera { x: -2, y: -6, zoom: 4 };
+
+        // Cell (1, 2) covers screen pixels 2..6 across and 2..6 down
+        let pixels = render_view(&state, &camera, 8, 8);
+        let lit = |x: usize, y: usize| pixels[(y * 8 + x) * 3] == 255;
+        assert!(lit(2, 2) && lit(5, 5));
+        assert!(!lit(1, 2) && !lit(6, 5) && !lit(2, 1));
+
+        // Off the board is black, however far away it is
+        let far = Camera { x: 100, y: 100, zoom: 1 };
+        assert!(render_view(&state, &far, 8, 8).iter().all(|&byte| byte == 0));
+    }
+}
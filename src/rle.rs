@@ -0,0 +1,346 @@
+/* ###############################################################
+# Run Length Encoded (RLE) pattern files.                        #
+# See https://conwaylife.com/wiki/Run_Length_Encoded for details. #
+############################################################### */
+
+use std::io::{self, BufRead};
+
+// Lines of encoded cells are kept at or below this length, as the format recommends
+const LINE_LENGTH: usize = 70;
+
+// Encode a grid (indexed as `state[x][y]`) as an RLE pattern with the given rule string
+pub fn encode(state: &[Vec<bool>], rule: &str) -> String {
+    let width = state.len();
+    let height = state.first().map_or(0, |column| column.len());
+
+    let mut tokens = Vec::new();
+    let mut cursor_y = 0;
+    for y in 0..height {
+        // Dead cells after the last live cell in a row are implied, as are empty rows
+        let row_end = match (0..width).rev().find(|&x| state[x][y]) {
+            Some(x) => x + 1,
+            None => continue,
+        };
+        if y > cursor_y {
+            tokens.push(run(y - cursor_y, '$'));
+            cursor_y = y;
+        }
+
+        let mut x = 0;
+        while x < row_end {
+            let alive = state[x][y];
+            let length = (x..row_end).take_while(|&x2| state[x2][y] == alive).count();
+            tokens.push(run(length, if alive { 'o' } else { 'b' }));
+            x += length;
+        }
+    }
+    tokens.push("!".to_string());
+
+    // Wrap the tokens into lines
+    let mut output = format!("x = {}, y = {}, rule = {}\n", width, height, rule);
+    let mut line_length = 0;
+    for token in tokens {
+        if line_length + token.len() > LINE_LENGTH {
+            output.push('\n');
+            line_length = 0;
+        }
+        line_length += token.len();
+        output.push_str(&token);
+    }
+    output.push('\n');
+    return output;
+}
+
+// Format a single run, omitting the count when it's 1
+fn run(length: usize, tag: char) -> String {
+    if length == 1 {
+        return tag.to_string();
+    } else {
+        return format!("{}{}", length, tag);
+    }
+}
+
+// The `x = 3, y = 3, rule = B3/S23` line at the top of a pattern
+#[derive(Clone, Debug, PartialEq)]
+pub struct Header {
+    pub width: usize,
+    pub height: usize,
+    pub rule: Option<String>,
+    pub comments: Vec<String>,  // `#` lines before the header, e.g. `#C A comment`
+}
+
+// Incremental decoder that yields the (x, y) coordinates of live cells as it reads them, so only
+// one line of the pattern is held in memory at a time
+pub struct Decoder<R: BufRead> {
+    pub header: Header,
+    reader: R,
+    line: Vec<u8>,  // Bytes of the line currently being decoded
+    position: usize,
+    count: usize,   // Run count read so far, or 0 if none
+    pending: usize, // Live cells left to yield from the current run
+    x: usize,
+    y: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Decoder<R> {
+    // Start decoding, reading up to and including the header line
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut line = String::new();
+        let mut comments = Vec::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Err(invalid_data("Pattern is missing its header line".to_string()));
+            }
+            let trimmed = line.trim();
+            if trimmed.starts_with('#') {
+                comments.push(trimmed.to_string());
+            } else if !trimmed.is_empty() {
+                break;
+            }
+        }
+
+        let mut header = parse_header(line.trim())?;
+        header.comments = comments;
+        return Ok(Decoder {
+            header: header,
+            reader: reader,
+            line: Vec::new(),
+            position: 0,
+            count: 0,
+            pending: 0,
+            x: 0,
+            y: 0,
+            done: false,
+        });
+    }
+
+    // Get the next byte of the body, reading another line if needed, or None at the end of input
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        while self.position >= self.line.len() {
+            self.line.clear();
+            self.position = 0;
+            if self.reader.read_until(b'\n', &mut self.line)? == 0 {
+                return Ok(None);
+            }
+            if self.line.first() == Some(&b'#') {
+                // Skip comments in the body
+                self.line.clear();
+            }
+        }
+        self.position += 1;
+        return Ok(Some(self.line[self.position - 1]));
+    }
+
+    // Take the current run count, which defaults to 1 when omitted
+    fn take_count(&mut self) -> usize {
+        let count = self.count.max(1);
+        self.count = 0;
+        return count;
+    }
+
+    // Move a coordinate along by the current run count, failing rather than overflowing on a
+    // run too long for any board
+    fn advance(&mut self, coordinate: usize) -> io::Result<usize> {
+        let count = self.take_count();
+        return coordinate.checked_add(count)
+            .ok_or_else(|| invalid_data("Run count is too large".to_string()));
+    }
+
+    // Decode one byte of the body
+    fn apply(&mut self, byte: u8) -> io::Result<()> {
+        match byte {
+            b'0'..=b'9' => {
+                let digit = (byte - b'0') as usize;
+                self.count = self.count.checked_mul(10).and_then(|n| n.checked_add(digit))
+                    .ok_or_else(|| invalid_data("Run count is too large".to_string()))?;
+            },
+            b'b' | b'.' => self.x = self.advance(self.x)?,
+            b'$' => {
+                self.y = self.advance(self.y)?;
+                self.x = 0;
+            },
+            b'!' => self.done = true,
+            // Any other state counts as alive
+            _ if byte.is_ascii_alphabetic() => {
+                self.pending = self.count.max(1);
+                self.advance(self.x)?;
+            },
+            _ if byte.is_ascii_whitespace() => {},
+            _ => {
+                let message = format!("Unexpected character `{}` in pattern", byte as char);
+                return Err(invalid_data(message));
+            },
+        }
+        return Ok(());
+    }
+}
+
+impl<R: BufRead> Iterator for Decoder<R> {
+    type Item = io::Result<(usize, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.pending > 0 {
+                self.pending -= 1;
+                self.x += 1;
+                return Some(Ok((self.x - 1, self.y)));
+            }
+            if self.done {
+                return None;
+            }
+
+            let byte = match self.next_byte() {
+                Ok(Some(byte)) => byte,
+                Ok(None) => {
+                    // Tolerate a missing `!` at the end of the file
+                    self.done = true;
+                    return None;
+                },
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                },
+            };
+            if let Err(e) = self.apply(byte) {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+// Parse a header line like `x = 3, y = 3, rule = B3/S23`
+fn parse_header(line: &str) -> io::Result<Header> {
+    let mut header = Header { width: 0, height: 0, rule: None, comments: Vec::new() };
+    let mut has_size = (false, false);
+    for field in line.split(',') {
+        let mut parts = field.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let value = parts.next().unwrap_or("").trim();
+        let parse_size = |value: &str| -> io::Result<usize> {
+            return value.parse()
+                .map_err(|_| invalid_data(format!("Invalid pattern size `{}`", value)));
+        };
+        match key {
+            "x" => {
+                header.width = parse_size(value)?;
+                has_size.0 = true;
+            },
+            "y" => {
+                header.height = parse_size(value)?;
+                has_size.1 = true;
+            },
+            "rule" => header.rule = Some(value.to_string()),
+            _ => {},  // Ignore anything else, like Golly's `pos`
+        }
+    }
+
+    if has_size != (true, true) {
+        return Err(invalid_data(format!("Invalid header line `{}`", line)));
+    }
+    return Ok(header);
+}
+
+fn invalid_data(message: String) -> io::Error {
+    return io::Error::new(io::ErrorKind::InvalidData, message);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_glider() {
+        // .o.
+        // ..o
+        // ooo
+        let mut state = vec![vec![false; 3]; 3];
+        for &(x, y) in &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            state[x][y] = true;
+        }
+        assert_eq!(encode(&state, "B3/S23"), "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+    }
+
+    #[test]
+    fn encode_skips_empty_rows() {
+        let mut state = vec![vec![false; 4]; 2];
+        state[0][0] = true;
+        state[1][3] = true;
+        assert_eq!(encode(&state, "B3/S23"), "x = 2, y = 4, rule = B3/S23\no3$bo!\n");
+    }
+
+    // Decode a whole pattern into its header and sorted live cells
+    fn decode(pattern: &str) -> io::Result<(Header, Vec<(usize, usize)>)> {
+        let decoder = Decoder::new(pattern.as_bytes())?;
+        let header = decoder.header.clone();
+        let mut cells = decoder.collect::<io::Result<Vec<_>>>()?;
+        cells.sort();
+        return Ok((header, cells));
+    }
+
+    #[test]
+    fn decode_glider() {
+        let (header, cells) = decode("#N Glider\n#C A comment\nx = 3, y = 3, rule = B3/S23\n\
+            bo$2bo$3o!\n").unwrap();
+        assert_eq!(header, Header {
+            width: 3,
+            height: 3,
+            rule: Some("B3/S23".to_string()),
+            comments: vec!["#N Glider".to_string(), "#C A comment".to_string()],
+        });
+        assert_eq!(cells, vec![(0, 2), (1, 0), (1, 2), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn decode_runs_across_lines() {
+        // Counts and runs may be split by line breaks
+        let (_, cells) = decode("x = 12, y = 3\n1\n1o$\n\n2$3b\no!").unwrap();
+        let mut expected: Vec<(usize, usize)> = (0..11).map(|x| (x, 0)).collect();
+        expected.push((3, 3));
+        expected.sort();
+        assert_eq!(cells, expected);
+    }
+
+    #[test]
+    fn decode_round_trips_encode() {
+        let mut state = vec![vec![false; 9]; 7];
+        for &(x, y) in &[(0, 0), (6, 0), (3, 4), (4, 4), (5, 4), (1, 8)] {
+            state[x][y] = true;
+        }
+        let (header, cells) = decode(&encode(&state, "B3/S23")).unwrap();
+        assert_eq!((header.width, header.height), (7, 9));
+        let mut expected = Vec::new();
+        for x in 0..7 {
+            for y in 0..9 {
+                if state[x][y] { expected.push((x, y)); }
+            }
+        }
+        assert_eq!(cells, expected);
+    }
+
+    #[test]
+    fn decode_rejects_bad_input() {
+        assert!(decode("bo$2bo$3o!").is_err());
+        assert!(decode("x = 3, y = 3\nbo$2b?o!").is_err());
+        assert!(decode("").is_err());
+
+        // Runs that would overflow are rejected rather than wrapping or panicking
+        let huge = format!("x = 3, y = 3\n{}b{}bo!", usize::MAX, usize::MAX);
+        assert!(decode(&huge).is_err());
+        assert!(decode(&format!("x = 3, y = 3\no{}$2$o!", usize::MAX)).is_err());
+        assert!(decode(&format!("x = 3, y = 3\n2b{}o!", usize::MAX)).is_err());
+    }
+
+    #[test]
+    fn encode_wraps_long_lines() {
+        // Alternating cells produce one token per cell
+        let mut state = vec![vec![false; 1]; 200];
+        for x in (0..200).step_by(2) {
+            state[x][0] = true;
+        }
+        let encoded = encode(&state, "B3/S23");
+        assert!(encoded.lines().all(|line| line.len() <= LINE_LENGTH));
+    }
+}
@@ -0,0 +1,240 @@
+/* ###########################################
+# Outer-totalistic rules in B/S notation.    #
+# A dead cell is born if its live neighbor   #
+# count is listed after B, and a live cell   #
+# survives if its count is listed after S.   #
+########################################### */
+
+use std::fmt;
+use std::str::FromStr;
+
+// Well-known rules, in the order they're cycled through
+pub const NAMED_RULES: &[(&str, &str)] = &[
+    ("Life",               "B3/S23"),
+    ("HighLife",           "B36/S23"),
+    ("Day & Night",        "B3678/S34678"),
+    ("Seeds",              "B2/S"),
+    ("Life without Death", "B3/S012345678"),
+    ("Maze",               "B3/S12345"),
+    ("Morley",             "B368/S245"),
+    ("2x2",                "B36/S125"),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rule {
+    pub birth: [bool; 9],     // Indexed by neighbor count
+    pub survival: [bool; 9],  // Indexed by neighbor count
+}
+
+impl Rule {
+    // Conway's Game of Life, B3/S23
+    pub fn life() -> Self {
+        let mut rule = Rule { birth: [false; 9], survival: [false; 9] };
+        rule.birth[3] = true;
+        rule.survival[2] = true;
+        rule.survival[3] = true;
+        return rule;
+    }
+
+    // Get the named rule after this one, wrapping around. Rules without a name go to the first.
+    pub fn next_named(&self) -> (&'static str, Rule) {
+        let rules: Vec<(&str, Rule)> = NAMED_RULES.iter()
+            .map(|&(name, rule)| (name, rule.parse().unwrap()))
+            .collect();
+        let index = rules.iter().position(|&(_, rule)| rule == *self)
+            .map_or(0, |index| (index + 1) % rules.len());
+        return rules[index];
+    }
+
+    // Get the next state of a cell with the given state and number of living neighbors
+    pub fn next_state(&self, alive: bool, neighbors: u8) -> bool {
+        let neighbors = neighbors as usize;
+        if alive {
+            return self.survival[neighbors];
+        } else {
+            return self.birth[neighbors];
+        }
+    }
+
+    // Get every rule that differs from this one by a single birth or survival count. Turning on
+    // B0 is left out, since it fills every empty cell at once.
+    pub fn mutations(&self) -> Vec<Rule> {
+        let mut rules = Vec::new();
+        for neighbors in 0..9 {
+            if neighbors > 0 || self.birth[0] {
+                let mut rule = *self;
+                rule.birth[neighbors] = !rule.birth[neighbors];
+                rules.push(rule);
+            }
+        }
+        for neighbors in 0..9 {
+            let mut rule = *self;
+            rule.survival[neighbors] = !rule.survival[neighbors];
+            rules.push(rule);
+        }
+        return rules;
+    }
+}
+
+impl Rule {
+    // Write the whole transition as CSV, one row for each state and neighbor count, for tools
+    // that take a rule as a lookup table. States are 0 for dead and 1 for alive.
+    pub fn to_csv(self) -> String {
+        let mut csv = "state,neighbors,next\n".to_string();
+        for &alive in &[false, true] {
+            for neighbors in 0..9 {
+                csv += &format!("{},{},{}\n", alive as u8, neighbors,
+                    self.next_state(alive, neighbors) as u8);
+            }
+        }
+        return csv;
+    }
+
+    // Write the rule as a Golly `.rule` file with a table of transitions. Golly looks a rule up
+    // by file name, so `name` should be the file's name without `.rule`.
+    pub fn to_golly_table(self, name: &str) -> String {
+        let mut table = format!("@RULE {}\n\n{}\n\n@TABLE\nn_states:2\nneighborhood:Moore\n\
+            symmetries:permute\n\n# C,N,NE,E,SE,S,SW,W,NW,C'\n", name, self);
+        // Under permute symmetry only the number of live neighbors matters, so listing them
+        // first with the rest dead covers every arrangement
+        for &alive in &[false, true] {
+            for neighbors in 0..9 {
+                let cells: Vec<String> = (0..8).map(|i| ((i < neighbors) as u8).to_string())
+                    .collect();
+                table += &format!("{},{},{}\n", alive as u8, cells.join(","),
+                    self.next_state(alive, neighbors as u8) as u8);
+            }
+        }
+        return table;
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        return Rule::life();
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| -> String {
+            return (0..9).filter(|&n| counts[n]).map(|n| n.to_string()).collect();
+        };
+        return write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival));
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    // Parse `B3/S23` style rules, in any case and order, as well as the older `23/3` S/B style
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_counts = |digits: &str| -> Result<[bool; 9], String> {
+            let mut counts = [false; 9];
+            for c in digits.chars() {
+                match c.to_digit(10) {
+                    Some(n) if n <= 8 => counts[n as usize] = true,
+                    _ => return Err(format!("Invalid neighbor count `{}` in rule `{}`", c, s)),
+                }
+            }
+            return Ok(counts);
+        };
+
+        let parts: Vec<&str> = s.trim().split('/').collect();
+        if parts.len() != 2 {
+            return Err(format!("Rule `{}` should have the form B3/S23", s));
+        }
+
+        let mut rule = Rule { birth: [false; 9], survival: [false; 9] };
+        let (first, second) = (parts[0], parts[1]);
+        let first_tag = first.chars().next().map(|c| c.to_ascii_uppercase());
+        let second_tag = second.chars().next().map(|c| c.to_ascii_uppercase());
+        match (first_tag, second_tag) {
+            (Some('B'), Some('S')) => {
+                rule.birth = parse_counts(&first[1..])?;
+                rule.survival = parse_counts(&second[1..])?;
+            },
+            (Some('S'), Some('B')) => {
+                rule.survival = parse_counts(&first[1..])?;
+                rule.birth = parse_counts(&second[1..])?;
+            },
+            _ => {
+                // Untagged rules list survival counts first
+                rule.survival = parse_counts(first)?;
+                rule.birth = parse_counts(second)?;
+            },
+        }
+        return Ok(rule);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_notations() {
+        let highlife = Rule::from_str("B36/S23").unwrap();
+        assert_eq!(Rule::from_str("b36/s23"), Ok(highlife));
+        assert_eq!(Rule::from_str("S23/B36"), Ok(highlife));
+        assert_eq!(Rule::from_str("23/36"), Ok(highlife));
+        assert_eq!(highlife.to_string(), "B36/S23");
+        assert_eq!(Rule::from_str("B3/S23"), Ok(Rule::life()));
+    }
+
+    #[test]
+    fn mutations_flip_one_count() {
+        let mutations = Rule::life().mutations();
+        assert_eq!(mutations.len(), 17);
+        assert!(mutations.contains(&Rule::from_str("B36/S23").unwrap()));
+        assert!(mutations.contains(&Rule::from_str("B3/S2").unwrap()));
+        assert!(!mutations.contains(&Rule::from_str("B03/S23").unwrap()));
+        assert!(!mutations.contains(&Rule::life()));
+    }
+
+    #[test]
+    fn next_named_cycles() {
+        let mut rule = Rule::life();
+        for _ in 0..NAMED_RULES.len() {
+            rule = rule.next_named().1;
+        }
+        assert_eq!(rule, Rule::life());
+        assert_eq!(Rule::from_str("B1/S1").unwrap().next_named().0, "Life");
+    }
+
+    #[test]
+    fn rule_tables_match_the_simulation() {
+        let rule = Rule::from_str("B36/S23").unwrap();
+        let csv = rule.to_csv();
+        let rows: Vec<Vec<u8>> = csv.lines().skip(1)
+            .map(|line| line.split(',').map(|n| n.parse().unwrap()).collect())
+            .collect();
+        assert_eq!(rows.len(), 18);
+
+        // Build each state and neighbor count on a board and see what updating it does
+        for row in &rows {
+            let mut game = ::GameOfLife::new(3, 3, 0);
+            game.rule = rule;
+            game.state[1][1] = row[0] == 1;
+            let around = [(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2), (0, 1)];
+            for &(x, y) in &around[..row[1] as usize] {
+                game.state[x][y] = true;
+            }
+            game.update();
+            assert_eq!(game.state[1][1] as u8, row[2], "{:?}", row);
+        }
+
+        let table = rule.to_golly_table("HighLife");
+        assert!(table.starts_with("@RULE HighLife\n"));
+        assert!(table.contains("\n0,1,1,1,1,1,1,0,0,1\n"));  // Born with 6
+        assert!(table.contains("\n1,1,1,1,0,0,0,0,0,1\n"));  // Survives with 3
+        assert!(table.contains("\n1,1,1,1,1,0,0,0,0,0\n"));  // Dies with 4
+    }
+
+    #[test]
+    fn parse_rejects_invalid_rules() {
+        assert!(Rule::from_str("B39/S23").is_err());
+        assert!(Rule::from_str("B3S23").is_err());
+        assert!(Rule::from_str("B3/S23/C4").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+/* ###############################################
+# Sparse backend that stores only live cells.    #
+# Each generation costs time proportional to the #
+# population rather than the board's area.       #
+############################################### */
+
+use std::collections::{HashMap, HashSet};
+use rule::Rule;
+use {GameOfLife, Topology, UpdateMode};
+
+// Generations between shrinking the sets back down to what the population needs
+pub const DEFAULT_GC_INTERVAL: u64 = 256;
+
+// A set is only shrunk if it holds at least this many times more entries than it's using, so
+// shrinking and regrowing doesn't thrash while the population wobbles
+const GC_SLACK: usize = 4;
+
+pub struct SparseLife {
+    pub cells: HashSet<(i32, i32)>,
+    pub width: i32,
+    pub height: i32,
+    pub topology: Topology,
+    pub rule: Rule,
+    pub generation: u64,
+    pub gc_interval: u64,            // Generations between shrinking the sets, 0 for never
+    tally: HashMap<(i32, i32), u8>,  // Live neighbor counts, kept between generations for reuse
+}
+
+impl SparseLife {
+    // Copy a game into the sparse backend, or return why it can't be represented
+    pub fn from_game(game: &GameOfLife) -> Result<Self, String> {
+        if game.topology == Topology::Reflect {
+            return Err("reflect topology isn't supported".to_string());
+        }
+        if game.update_mode != UpdateMode::Synchronous {
+            return Err("only synchronous updates are supported".to_string());
+        }
+        if game.arena.is_some() {
+            return Err("arenas aren't supported".to_string());
+        }
+        if !game.regions.is_empty() {
+            return Err("per-region rules aren't supported".to_string());
+        }
+        if game.previous.is_some() {
+            return Err("second-order rules aren't supported".to_string());
+        }
+        if game.decay.is_some() {
+            return Err("decay isn't supported".to_string());
+        }
+        if game.rule.birth[0] {
+            return Err("rules with B0 would fill every empty cell".to_string());
+        }
+
+        return Ok(SparseLife {
+            cells: game.live_cells().into_iter().collect(),
+            width: game.state_width as i32,
+            height: game.state_height as i32,
+            topology: game.topology,
+            rule: game.rule,
+            generation: game.generation,
+            gc_interval: DEFAULT_GC_INTERVAL,
+            tally: HashMap::new(),
+        });
+    }
+
+    // Map a possibly out-of-bounds coordinate onto the board, like `GameOfLife::resolve_coords`
+    fn resolve_coords(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        let (x, y) = match self.topology {
+            Topology::Torus    => (x.rem_euclid(self.width), y.rem_euclid(self.height)),
+            Topology::Cylinder => (x.rem_euclid(self.width), y),
+            Topology::Bounded | Topology::Reflect => (x, y),
+        };
+        if (0..self.width).contains(&x) && (0..self.height).contains(&y) {
+            return Some((x, y));
+        } else {
+            return None;
+        }
+    }
+
+    // Advance by one generation
+    pub fn update(&mut self) {
+        // Every live cell adds one to each of its neighbors' counts. Cells with no live
+        // neighbors never appear, which is what keeps this proportional to the population.
+        self.tally.clear();
+        for &(x, y) in &self.cells {
+            for yofs in -1..=1 {
+                for xofs in -1..=1 {
+                    if xofs == 0 && yofs == 0 {
+                        continue;
+                    }
+                    if let Some(neighbor) = self.resolve_coords(x + xofs, y + yofs) {
+                        *self.tally.entry(neighbor).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let mut next: HashSet<(i32, i32)> = self.tally.iter()
+            .filter(|&(cell, &count)| self.rule.next_state(self.cells.contains(cell), count))
+            .map(|(&cell, _)| cell)
+            .collect();
+        if self.rule.survival[0] {
+            // Isolated cells never get a count, so check them separately
+            next.extend(self.cells.iter().filter(|cell| !self.tally.contains_key(cell)));
+        }
+        self.cells = next;
+        self.generation += 1;
+        if self.gc_interval > 0 && self.generation.is_multiple_of(self.gc_interval) {
+            self.collect_garbage();
+        }
+        self.check_invariants();
+    }
+
+    // Get roughly how many bytes the live cells and neighbor counts take up, going by how many
+    // entries the sets have room for rather than how many they hold
+    pub fn memory_used(&self) -> usize {
+        return self.cells.capacity() * std::mem::size_of::<(i32, i32)>()
+            + self.tally.capacity() * std::mem::size_of::<((i32, i32), u8)>();
+    }
+
+    // Give back the room the sets kept from when the population was larger. The tally is only
+    // ever cleared, so after a spike it holds its peak capacity until this runs. Returns roughly
+    // how many bytes were freed.
+    pub fn collect_garbage(&mut self) -> usize {
+        let before = self.memory_used();
+        if self.cells.capacity() > GC_SLACK * self.cells.len().max(1) {
+            self.cells.shrink_to_fit();
+        }
+        // The tally is refilled next generation, with about as many entries as it has now
+        if self.tally.capacity() > GC_SLACK * self.tally.len().max(1) {
+            self.tally.shrink_to_fit();
+        }
+        return before - self.memory_used();
+    }
+
+    // Check, in debug builds only, that every stored cell and tallied neighbor is on the board.
+    // A coordinate that slipped past `resolve_coords`, like one wrapped the wrong way at an edge,
+    // would otherwise live on forever, since nothing ever iterates the board to drop it.
+    fn check_invariants(&self) {
+        let on_board = |&(x, y): &(i32, i32)| {
+            (0..self.width).contains(&x) && (0..self.height).contains(&y)
+        };
+        debug_assert!(self.cells.iter().all(on_board), "a live cell is off the board");
+        debug_assert!(self.tally.keys().all(on_board), "a neighbor count is off the board");
+    }
+
+    // Get the coordinates of every live cell
+    pub fn live_cells(&self) -> Vec<(i32, i32)> {
+        return self.cells.iter().cloned().collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparse_matches_dense() {
+        for &topology in &[Topology::Bounded, Topology::Torus, Topology::Cylinder] {
+            let mut game = GameOfLife::new(32, 24, 5);
+            game.topology = topology;
+            game.randomize_state();
+            let mut sparse = SparseLife::from_game(&game).unwrap();
+            for _ in 0..30 {
+                game.update();
+                sparse.update();
+            }
+
+            let mut expected = game.live_cells();
+            let mut cells = sparse.live_cells();
+            expected.sort();
+            cells.sort();
+            assert_eq!(cells, expected, "{:?}", topology);
+        }
+    }
+
+    #[test]
+    fn garbage_collection_reclaims_memory_after_a_spike() {
+        let mut game = GameOfLife::new(128, 128, 3);
+        game.randomize_state();
+        let mut sparse = SparseLife::from_game(&game).unwrap();
+        sparse.gc_interval = 0;
+        sparse.update();
+        let peak = sparse.memory_used();
+        let peak_tally = sparse.tally.capacity();
+
+        // The population collapses to a single block, but the tally holds on to its capacity
+        sparse.cells = [(10, 10), (10, 11), (11, 10), (11, 11)].iter().cloned().collect();
+        sparse.update();
+        assert_eq!(sparse.tally.capacity(), peak_tally);
+        let before = sparse.memory_used();
+
+        let reclaimed = sparse.collect_garbage();
+        assert!(reclaimed > before / 2, "reclaimed {} of {} bytes", reclaimed, before);
+        assert_eq!(sparse.memory_used(), before - reclaimed);
+        assert_eq!(sparse.cells.len(), 4);
+
+        // Collecting happens by itself every interval
+        sparse.cells = game.live_cells().into_iter().collect();
+        sparse.gc_interval = 2;
+        sparse.update();
+        sparse.cells = [(10, 10), (10, 11), (11, 10), (11, 11)].iter().cloned().collect();
+        sparse.update();
+        assert!(sparse.memory_used() < peak / 2);
+    }
+
+    #[test]
+    fn sparse_rejects_unsupported_configurations() {
+        let mut game = GameOfLife::new(8, 8, 0);
+        game.topology = Topology::Reflect;
+        assert!(SparseLife::from_game(&game).is_err());
+    }
+}
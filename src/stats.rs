@@ -0,0 +1,307 @@
+/* ##############################################
+# Measurements of how the board changes from    #
+# one generation to the next.                   #
+############################################## */
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+// How many generations the running average of changed cells covers
+const ACTIVITY_WINDOW: usize = 32;
+
+// Longest oscillator period that counts as the board having stabilized
+const MAX_STABLE_PERIOD: usize = 60;
+
+// Count the cells that differ between two states, i.e. their Hamming distance
+pub fn hamming_distance(previous: &[Vec<bool>], state: &[Vec<bool>]) -> usize {
+    return previous.iter().zip(state)
+        .map(|(a, b)| a.iter().zip(b).filter(|&(a, b)| a != b).count())
+        .sum();
+}
+
+// Estimate the board's spatial entropy, in bits. The board is cut into 2x2 blocks starting from
+// the top-left corner, leaving out a last row or column that doesn't fill a block, and each
+// block is one of 16 arrangements of live cells. The estimate is the Shannon entropy of how often
+// each arrangement appears, -sum(p * log2(p)), from 0 when every block is the same, like on an
+// empty board, to 4 when all 16 are equally common, like in a soup of density 0.5.
+pub fn block_entropy(state: &[Vec<bool>]) -> f64 {
+    let height = state.first().map_or(0, |column| column.len());
+    let mut counts = [0usize; 16];
+    for x in (0..state.len() / 2 * 2).step_by(2) {
+        for y in (0..height / 2 * 2).step_by(2) {
+            let block = state[x][y] as usize
+                | (state[x + 1][y] as usize) << 1
+                | (state[x][y + 1] as usize) << 2
+                | (state[x + 1][y + 1] as usize) << 3;
+            counts[block] += 1;
+        }
+    }
+
+    let total = counts.iter().sum::<usize>() as f64;
+    return counts.iter().filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum();
+}
+
+// Recent numbers of changed cells per generation. A running average that trends to zero means
+// the board is settling down.
+pub struct Activity {
+    recent: VecDeque<usize>,  // Newest last
+}
+
+impl Activity {
+    pub fn new() -> Self {
+        return Activity { recent: VecDeque::with_capacity(ACTIVITY_WINDOW) };
+    }
+
+    // Record the number of cells that changed in a generation
+    pub fn record(&mut self, changed: usize) {
+        if self.recent.len() == ACTIVITY_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(changed);
+    }
+
+    // Forget everything recorded, like after a reset
+    pub fn clear(&mut self) {
+        self.recent.clear();
+    }
+
+    // Get the number of cells that changed in the last generation recorded
+    pub fn last(&self) -> Option<usize> {
+        return self.recent.back().cloned();
+    }
+
+    // Get the average number of changed cells over the window, or None if nothing's recorded
+    pub fn average(&self) -> Option<f64> {
+        if self.recent.is_empty() {
+            return None;
+        }
+        return Some(self.recent.iter().sum::<usize>() as f64 / self.recent.len() as f64);
+    }
+}
+
+// Counts generations over each second of wall-clock time, to tell how fast the simulation is
+// really running
+pub struct RateMeter {
+    elapsed: f32,         // Seconds into the current measurement
+    updates: u32,         // Generations so far in the current measurement
+    pub rate: Option<f32>,  // Generations per second over the last full measurement
+}
+
+impl RateMeter {
+    pub fn new() -> Self {
+        return RateMeter { elapsed: 0.0, updates: 0, rate: None };
+    }
+
+    // Count the generations advanced in a frame that took `elapsed` seconds
+    pub fn tick(&mut self, elapsed: f32, updates: u32) {
+        self.elapsed += elapsed;
+        self.updates += updates;
+        if self.elapsed >= 1.0 {
+            self.rate = Some(self.updates as f32 / self.elapsed);
+            self.elapsed = 0.0;
+            self.updates = 0;
+        }
+    }
+
+    // Start measuring again, like when the simulation is paused
+    pub fn clear(&mut self) {
+        *self = RateMeter::new();
+    }
+}
+
+// Holds frames to a steady rate by working out how long each one should wait before it's shown.
+// Frames are due at even intervals, so a frame that finishes early waits for its turn; one that
+// falls behind starts the schedule again from when it's shown, rather than the next few frames
+// rushing out to make up for it.
+pub struct FrameLimiter {
+    interval: Duration,     // Time between frames
+    next: Option<Instant>,  // When the next frame is due, once the first has been shown
+}
+
+impl FrameLimiter {
+    pub fn new(frames_per_second: f64) -> Self {
+        return FrameLimiter {
+            interval: Duration::from_secs_f64(1.0 / frames_per_second),
+            next: None,
+        };
+    }
+
+    // Get how long a frame finished at `now` should wait before it's shown. A frame that's a
+    // little late goes out at once and the next is still due when it would have been, but one
+    // that's missed its whole slot starts the schedule again from itself.
+    pub fn delay(&mut self, now: Instant) -> Duration {
+        let due = self.next.unwrap_or(now);
+        if now < due + self.interval {
+            self.next = Some(due + self.interval);
+        } else {
+            self.next = Some(now + self.interval);
+        }
+        return due.saturating_duration_since(now);
+    }
+}
+
+// Something worth telling the user about
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    Extinct,        // Every cell died
+    Stable(usize),  // The board repeats with this period, 1 for still lifes
+}
+
+// Spots the board dying out or settling into a repeating cycle. Each is reported once, until the
+// board does something new again.
+pub struct Detector {
+    recent: VecDeque<u64>,  // Hashes of recent states, newest last
+    reported: bool,
+}
+
+impl Detector {
+    pub fn new() -> Self {
+        return Detector { recent: VecDeque::with_capacity(MAX_STABLE_PERIOD), reported: false };
+    }
+
+    // Forget the history, like after a reset
+    pub fn clear(&mut self) {
+        self.recent.clear();
+        self.reported = false;
+    }
+
+    // Look at the next generation's state, returning an event if it's the first sign of one
+    pub fn observe(&mut self, state: &[Vec<bool>]) -> Option<Event> {
+        let mut hasher = DefaultHasher::new();
+        state.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        // The newest earlier occurrence gives the shortest period
+        let period = self.recent.iter().rev().position(|&seen| seen == hash).map(|i| i + 1);
+        if self.recent.len() == MAX_STABLE_PERIOD {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(hash);
+
+        let period = match period {
+            Some(period) => period,
+            None => {
+                self.reported = false;
+                return None;
+            },
+        };
+        if self.reported {
+            return None;
+        }
+        self.reported = true;
+        if state.iter().flatten().any(|&alive| alive) {
+            return Some(Event::Stable(period));
+        } else {
+            return Some(Event::Extinct);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GameOfLife;
+
+    #[test]
+    fn hamming_distance_and_running_average() {
+        let a = vec![vec![true, false], vec![false, false]];
+        let b = vec![vec![false, false], vec![true, true]];
+        assert_eq!(hamming_distance(&a, &b), 3);
+        assert_eq!(hamming_distance(&a, &a), 0);
+
+        let mut activity = Activity::new();
+        assert_eq!(activity.average(), None);
+        activity.record(4);
+        activity.record(2);
+        assert_eq!((activity.last(), activity.average()), (Some(2), Some(3.0)));
+
+        // Only the latest window counts
+        for _ in 0..ACTIVITY_WINDOW {
+            activity.record(1);
+        }
+        assert_eq!(activity.average(), Some(1.0));
+    }
+
+    #[test]
+    fn block_entropy_ranges_from_order_to_chaos() {
+        let empty = vec![vec![false; 6]; 6];
+        assert_eq!(block_entropy(&empty), 0.0);
+
+        // Half the blocks full and half empty is one bit
+        let mut halves = vec![vec![false; 4]; 4];
+        halves[0] = vec![true; 4];
+        halves[1] = vec![true; 4];
+        assert_eq!(block_entropy(&halves), 1.0);
+
+        // Every arrangement once is the most there can be, and odd edges are left out
+        let mut all = vec![vec![false; 9]; 9];
+        for block in 0..16 {
+            let (x, y) = (block % 4 * 2, block / 4 * 2);
+            all[x][y] = block & 1 != 0;
+            all[x + 1][y] = block & 2 != 0;
+            all[x][y + 1] = block & 4 != 0;
+            all[x + 1][y + 1] = block & 8 != 0;
+        }
+        all[8] = vec![true; 9];
+        assert!((block_entropy(&all) - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rate_meter_measures_each_second() {
+        let mut meter = RateMeter::new();
+        for _ in 0..9 {
+            meter.tick(0.1, 2);
+        }
+        assert_eq!(meter.rate, None);
+        meter.tick(0.1, 2);
+        assert!((meter.rate.unwrap() - 20.0).abs() < 0.01);
+        meter.clear();
+        assert_eq!(meter.rate, None);
+    }
+
+    #[test]
+    fn frame_limiter_keeps_an_even_schedule() {
+        let mut limiter = FrameLimiter::new(10.0);
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        assert_eq!(limiter.delay(at(0)), Duration::ZERO);
+
+        // Early frames wait for their turn, however long they took
+        assert_eq!(limiter.delay(at(30)), Duration::from_millis(70));
+        assert_eq!(limiter.delay(at(120)), Duration::from_millis(80));
+
+        // A frame that's a little late goes out at once without moving the schedule
+        assert_eq!(limiter.delay(at(305)), Duration::ZERO);
+        assert_eq!(limiter.delay(at(350)), Duration::from_millis(50));
+
+        // One that's missed its whole slot starts the schedule again from it
+        assert_eq!(limiter.delay(at(650)), Duration::ZERO);
+        assert_eq!(limiter.delay(at(660)), Duration::from_millis(90));
+    }
+
+    #[test]
+    fn detector_reports_each_event_once() {
+        // A blinker is found to have period 2 once it's been seen twice
+        let mut game = GameOfLife::new(5, 5, 0);
+        for x in 1..4 { game.state[x][2] = true; }
+        let mut detector = Detector::new();
+        let mut events = Vec::new();
+        for _ in 0..6 {
+            events.push(detector.observe(&game.state));
+            game.update();
+        }
+        assert_eq!(events, vec![None, None, Some(Event::Stable(2)), None, None, None]);
+
+        detector.clear();
+        game.empty_state();
+        assert_eq!(detector.observe(&game.state), None);
+        assert_eq!(detector.observe(&game.state), Some(Event::Extinct));
+    }
+}
@@ -0,0 +1,106 @@
+/* ############################################
+# Scalable Vector Graphics (SVG) export.      #
+# Live cells become <rect>s; dead cells are   #
+# left out to keep the file small.            #
+############################################ */
+
+use Rect;
+
+// How an exported pattern looks
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgStyle {
+    pub cell_size: u32,              // Width and height of each cell, in SVG units
+    pub live_color: String,          // Any SVG color, e.g. `black` or `#ff8800`
+    pub background: Option<String>,  // Color of the whole image, or transparent if None
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        return SvgStyle {
+            cell_size: 10,
+            live_color: "black".to_string(),
+            background: Some("white".to_string()),
+        };
+    }
+}
+
+// Check that a color can be written into an attribute as-is. This accepts names and hex codes,
+// which covers everything you'd reasonably pass on the command line.
+pub fn is_valid_color(color: &str) -> bool {
+    return !color.is_empty() && color.chars().all(|c| c.is_ascii_alphanumeric() || c == '#');
+}
+
+// Encode the live cells of a grid (indexed as `state[x][y]`) within `bounds` as an SVG image.
+// Coordinates are relative to the top-left corner of `bounds`.
+pub fn encode(state: &[Vec<bool>], bounds: Rect, style: &SvgStyle) -> String {
+    let size = style.cell_size as usize;
+    let width = bounds.width * size;
+    let height = bounds.height * size;
+
+    let mut output = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+            viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n",
+        width, height, width, height);
+    if let Some(background) = &style.background {
+        output.push_str(&format!(
+            "<rect width=\"{}\" height=\"{}\" fill=\"{}\"/>\n", width, height, background));
+    }
+
+    output.push_str(&format!("<g fill=\"{}\">\n", style.live_color));
+    for y in 0..bounds.height {
+        for x in 0..bounds.width {
+            if state[bounds.x + x][bounds.y + y] {
+                output.push_str(&format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>\n",
+                    x * size, y * size, size, size));
+            }
+        }
+    }
+    output.push_str("</g>\n</svg>\n");
+    return output;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_is_well_formed() {
+        let mut state = vec![vec![false; 8]; 8];
+        for &(x, y) in &[(3, 2), (4, 3), (2, 4), (3, 4), (4, 4)] {
+            state[x][y] = true;
+        }
+        let bounds = Rect { x: 2, y: 2, width: 3, height: 3 };
+        let style = SvgStyle { background: None, ..SvgStyle::default() };
+        let svg = encode(&state, bounds, &style);
+
+        // One root element, with every element inside it closed
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<svg").count(), 1);
+        assert_eq!(svg.matches("<g ").count(), svg.matches("</g>").count());
+        assert_eq!(svg.matches('<').count(), svg.matches('>').count());
+        let mut rects = svg.lines().filter(|line| line.starts_with("<rect"));
+        assert!(rects.all(|line| line.ends_with("/>")));
+
+        // One rect per live cell, trimmed to the bounding box
+        assert_eq!(svg.matches("<rect").count(), 5);
+        assert!(svg.contains("width=\"30\" height=\"30\""));
+        assert!(svg.contains("<rect x=\"10\" y=\"0\""));
+    }
+
+    #[test]
+    fn encode_background_adds_one_rect() {
+        let state = vec![vec![true; 2]; 2];
+        let bounds = Rect { x: 0, y: 0, width: 2, height: 2 };
+        let svg = encode(&state, bounds, &SvgStyle::default());
+        assert_eq!(svg.matches("<rect").count(), 5);
+    }
+
+    #[test]
+    fn colors_are_validated() {
+        assert!(is_valid_color("black"));
+        assert!(is_valid_color("#ff8800"));
+        assert!(!is_valid_color("\"/><script"));
+        assert!(!is_valid_color(""));
+    }
+}
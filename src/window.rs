@@ -0,0 +1,122 @@
+/* ###############################################
+# Window placement: moving the window and taking #
+# away its title bar and borders once the engine #
+# has opened it, since the binding can only ask  #
+# for full screen and vsync. Works under X11.    #
+############################################### */
+
+// Where the window goes and whether it has decorations. The default leaves both to the window
+// manager.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Placement {
+    pub position: Option<(i32, i32)>,  // Top-left corner on the screen, in pixels
+    pub borderless: bool,              // Whether to ask for no title bar or borders
+}
+
+impl Placement {
+    // Check whether there's anything to change from how the engine opens the window
+    pub fn is_default(&self) -> bool {
+        return self.position.is_none() && !self.borderless;
+    }
+}
+
+// Parse a screen position written as `x,y`. Either can be negative, for monitors to the left of
+// or above the primary one.
+pub fn parse_position(s: &str) -> Result<(i32, i32), String> {
+    let numbers = s.split(',')
+        .map(|n| n.trim().parse::<i32>())
+        .collect::<Result<Vec<i32>, _>>()
+        .map_err(|_| format!("Invalid window position `{}`", s))?;
+    match numbers[..] {
+        [x, y] => return Ok((x, y)),
+        _ => return Err(format!("Expected a window position as `x,y`, got `{}`", s)),
+    }
+}
+
+// Apply a placement to the engine's window. Has to be called from the engine's thread, like
+// `on_user_create`, where its OpenGL context is current, since that's the only way to find the
+// window.
+#[cfg(target_os = "linux")]
+pub fn place(placement: Placement) -> Result<(), String> {
+    if placement.is_default() {
+        return Ok(());
+    }
+
+    // SAFETY: the display and window come from the engine's current OpenGL context and stay
+    // open while it runs, and the hints are the five longs the property is made of
+    unsafe {
+        let display = x11::glXGetCurrentDisplay();
+        let window = x11::glXGetCurrentDrawable();
+        if display.is_null() || window == 0 {
+            return Err("Couldn't find the window".to_string());
+        }
+        if placement.borderless {
+            let name = b"_MOTIF_WM_HINTS\0";
+            let hints_atom = x11::XInternAtom(display, name.as_ptr() as *const _, 0);
+            // Motif's flags, functions, decorations, input mode and status, with only the
+            // decorations set, to none
+            let hints: [std::os::raw::c_long; 5] = [x11::MWM_HINTS_DECORATIONS, 0, 0, 0, 0];
+            x11::XChangeProperty(display, window, hints_atom, hints_atom, 32,
+                x11::PROP_MODE_REPLACE, hints.as_ptr() as *const _, hints.len() as i32);
+        }
+        if let Some((x, y)) = placement.position {
+            x11::XMoveWindow(display, window, x, y);
+        }
+        x11::XFlush(display);
+    }
+    return Ok(());
+}
+
+// Apply a placement to the engine's window, which needs X11
+#[cfg(not(target_os = "linux"))]
+pub fn place(placement: Placement) -> Result<(), String> {
+    if placement.is_default() {
+        return Ok(());
+    }
+    return Err("Borderless and positioned windows are only supported under X11".to_string());
+}
+
+// The few Xlib and GLX calls needed, from the libraries the engine already links
+#[cfg(target_os = "linux")]
+mod x11 {
+    use std::os::raw::{c_char, c_int, c_long, c_uchar, c_ulong, c_void};
+
+    pub const MWM_HINTS_DECORATIONS: c_long = 1 << 1;
+    pub const PROP_MODE_REPLACE: c_int = 0;
+
+    #[link(name = "X11")]
+    extern "C" {
+        pub fn XInternAtom(display: *mut c_void, name: *const c_char, only_if_exists: c_int)
+            -> c_ulong;
+        pub fn XChangeProperty(display: *mut c_void, window: c_ulong, property: c_ulong,
+            kind: c_ulong, format: c_int, mode: c_int, data: *const c_uchar, elements: c_int)
+            -> c_int;
+        pub fn XMoveWindow(display: *mut c_void, window: c_ulong, x: c_int, y: c_int) -> c_int;
+        pub fn XFlush(display: *mut c_void) -> c_int;
+    }
+
+    #[link(name = "GL")]
+    extern "C" {
+        pub fn glXGetCurrentDisplay() -> *mut c_void;
+        pub fn glXGetCurrentDrawable() -> c_ulong;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_window_positions() {
+        assert_eq!(parse_position("100,40"), Ok((100, 40)));
+        assert_eq!(parse_position(" -1920 , 0 "), Ok((-1920, 0)));
+        assert!(parse_position("100").is_err());
+        assert!(parse_position("100,40,3").is_err());
+        assert!(parse_position("left,top").is_err());
+
+        // Nothing to do unless something was asked for
+        assert!(Placement::default().is_default());
+        assert_eq!(place(Placement::default()), Ok(()));
+        assert!(!Placement { position: None, borderless: true }.is_default());
+    }
+}